@@ -0,0 +1,403 @@
+//! Sampling of keyframer tracks and node transforms at arbitrary frames.
+//!
+//! Matrices use the same layout as [`TriMesh::local`](crate::mesh::TriMesh::local): the images of the three axes
+//! followed by the translation, applied to row vectors.
+
+use crate::keyframer::{Key, Keyframer, Rotation, Track};
+use crate::math::{self, Matrix4x3, Quat, Vec3};
+
+/// Transform of a keyframer node at a given frame.
+///
+/// Both matrices move the pivot of the node to its origin first, so they map the vertices of the object in its
+/// local mesh coordinates. Children are placed relative to the parent without its pivot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeTransform {
+  /// Transform relative to the parent node.
+  pub local: [[f32; 3]; 4],
+  /// Transform relative to the scene.
+  pub world: [[f32; 3]; 4],
+}
+
+/// Position of a frame relative to the keys of a track.
+enum Segment {
+  Key(usize),
+  /// Between the key and the next one, with the eased interpolation parameter in the `0.0..1.0` range.
+  Between(usize, f32),
+}
+
+impl<T> Track<T> {
+  /// Finds the keys surrounding the frame, wrapping it into the range of the track for looping and repeating tracks
+  /// and clamping it otherwise.
+  fn segment(&self, frame: f32) -> Option<Segment> {
+    let (first, last) = (self.keys.first()?, self.keys.last()?);
+    let (start, end) = (frame_number(first.frame), frame_number(last.frame));
+    let mut frame = frame;
+    if (self.is_loop() || self.is_repeat()) && end > start && !(start..=end).contains(&frame) {
+      frame = start + (frame - start).rem_euclid(end - start);
+    }
+
+    let next = self.keys.iter().position(|key| frame_number(key.frame) > frame);
+    Some(match next {
+      None => Segment::Key(self.keys.len() - 1),
+      Some(0) => Segment::Key(0),
+      Some(next) => {
+        let (from, to) = (&self.keys[next - 1], &self.keys[next]);
+        let t = (frame - frame_number(from.frame)) / frame_number(to.frame.abs_diff(from.frame));
+        Segment::Between(next - 1, ease(t, from.ease_from, to.ease_to))
+      }
+    })
+  }
+}
+
+/// Converts a frame number for interpolation. Frame numbers are far below the precision limit of `f32`.
+#[allow(clippy::cast_precision_loss)]
+const fn frame_number(frame: u32) -> f32 {
+  frame as f32
+}
+
+/// Applies the ease from and ease to values of the keys around a segment to its interpolation parameter.
+fn ease(t: f32, from: f32, to: f32) -> f32 {
+  let sum = from + to;
+  if sum <= 0.0 {
+    return t;
+  }
+
+  let (from, to) = if sum > 1.0 { (from / sum, to / sum) } else { (from, to) };
+  let k = 1.0 / (2.0 - from - to);
+  if t < from {
+    k / from * t * t
+  } else if t < 1.0 - to {
+    k * 2.0f32.mul_add(t, -from)
+  } else {
+    let t = 1.0 - t;
+    (k / to * t).mul_add(-t, 1.0)
+  }
+}
+
+impl Track<[f32; 3]> {
+  /// Samples the track at the given frame with Kochanek-Bartels spline interpolation, or returns [`None`] if it has
+  /// no keys.
+  #[must_use]
+  pub fn sample(&self, frame: f32) -> Option<[f32; 3]> {
+    Some(match self.segment(frame)? {
+      Segment::Key(index) => self.keys[index].value,
+      Segment::Between(index, t) => {
+        let (from, to) = (self.keys[index].value, self.keys[index + 1].value);
+        let out_tangent = self.tangents(index).1;
+        let in_tangent = self.tangents(index + 1).0;
+
+        let (t2, t3) = (t * t, t * t * t);
+        let h01 = 3.0f32.mul_add(t2, -2.0 * t3);
+        let h00 = 1.0 - h01;
+        let h10 = 2.0f32.mul_add(-t2, t3) + t;
+        let h11 = t3 - t2;
+        math::add(
+          math::add(math::scale(from, h00), math::scale(out_tangent, h10)),
+          math::add(math::scale(to, h01), math::scale(in_tangent, h11)),
+        )
+      }
+    })
+  }
+
+  /// Returns the incoming and outgoing tangents of a key.
+  fn tangents(&self, index: usize) -> (Vec3, Vec3) {
+    let keys = &self.keys;
+    let key = &keys[index];
+    let count = keys.len();
+    // The last key of a looping track stands for the first one, so the neighbours wrap around it. Files do not
+    // guarantee that keys are in order, so the gaps between frames are taken without a sign.
+    let looping = self.is_loop() && count > 2;
+    let previous = match index {
+      0 if looping => Some((&keys[count - 2], keys[count - 1].frame.abs_diff(keys[count - 2].frame))),
+      0 => None,
+      _ => Some((&keys[index - 1], key.frame.abs_diff(keys[index - 1].frame))),
+    };
+    let next = match index {
+      _ if index + 1 < count => Some((&keys[index + 1], keys[index + 1].frame.abs_diff(key.frame))),
+      _ if looping => Some((&keys[1], keys[1].frame.abs_diff(keys[0].frame))),
+      _ => None,
+    };
+
+    let delta = |other: Option<(&Key<Vec3>, u32)>, towards: bool| {
+      other.map(|(other, _)| {
+        if towards {
+          math::sub(key.value, other.value)
+        } else {
+          math::sub(other.value, key.value)
+        }
+      })
+    };
+    let (delta_previous, delta_next) = match (delta(previous, true), delta(next, false)) {
+      (Some(previous), Some(next)) => (previous, next),
+      (Some(previous), None) => (previous, previous),
+      (None, Some(next)) => (next, next),
+      (None, None) => return ([0.0; 3], [0.0; 3]),
+    };
+
+    let (tension, continuity, bias) = (1.0 - key.tension, key.continuity, key.bias);
+    let mut in_tangent = math::add(
+      math::scale(delta_previous, tension * (1.0 - continuity) * (1.0 + bias) / 2.0),
+      math::scale(delta_next, tension * (1.0 + continuity) * (1.0 - bias) / 2.0),
+    );
+    let mut out_tangent = math::add(
+      math::scale(delta_previous, tension * (1.0 + continuity) * (1.0 + bias) / 2.0),
+      math::scale(delta_next, tension * (1.0 - continuity) * (1.0 - bias) / 2.0),
+    );
+
+    // Keys are not evenly spaced, so the tangents are adjusted to the length of the segment they are used on.
+    if let (Some((_, frames_previous)), Some((_, frames_next))) = (previous, next) {
+      let total = frame_number(frames_previous + frames_next);
+      if total > 0.0 {
+        in_tangent = math::scale(in_tangent, 2.0 * frame_number(frames_previous) / total);
+        out_tangent = math::scale(out_tangent, 2.0 * frame_number(frames_next) / total);
+      }
+    }
+
+    (in_tangent, out_tangent)
+  }
+}
+
+impl Track<Rotation> {
+  /// Samples the track at the given frame as a quaternion stored as `[x, y, z, w]`, or returns [`None`] if it has
+  /// no keys.
+  ///
+  /// Every key rotates relative to the previous one, so the rotations are accumulated from the first key and the
+  /// rotation of the next key is applied partially between two keys. This keeps rotations of more than half a turn
+  /// between keys intact.
+  #[must_use]
+  pub fn sample(&self, frame: f32) -> Option<[f32; 4]> {
+    let segment = self.segment(frame)?;
+    let (index, t) = match segment {
+      Segment::Key(index) => (index, 0.0),
+      Segment::Between(index, t) => (index, t),
+    };
+
+    let mut rotation = math::QUAT_IDENTITY;
+    for key in &self.keys[..=index] {
+      rotation = math::quat_mul(key_rotation(&key.value, 1.0), rotation);
+    }
+    if t > 0.0 {
+      rotation = math::quat_mul(key_rotation(&self.keys[index + 1].value, t), rotation);
+    }
+
+    Some(rotation)
+  }
+}
+
+/// Returns the given fraction of the rotation of a key.
+///
+/// Angles are stored clockwise around the axis, the opposite of the usual right-handed convention.
+fn key_rotation(rotation: &Rotation, fraction: f32) -> Quat {
+  math::quat_from_axis_angle(rotation.axis, -rotation.angle * fraction)
+}
+
+impl Keyframer {
+  /// Evaluates the transforms of all nodes at the given frame, in the same order as [`Keyframer::nodes`].
+  ///
+  /// Nodes without a position, rotation or scale track use the identity for it. The hierarchy is taken from
+  /// [`Node::parent`](crate::keyframer::Node::parent), so [`Keyframer::link`] has to be called again after editing
+  /// parent ids.
+  ///
+  /// # Panics
+  ///
+  /// Panics if [`Node::parent`](crate::keyframer::Node::parent) and
+  /// [`Node::children`](crate::keyframer::Node::children) have been edited into something other than a tree.
+  #[must_use]
+  pub fn evaluate(&self, frame: f32) -> Vec<NodeTransform> {
+    let frames: Vec<Matrix4x3> = self
+      .nodes
+      .iter()
+      .map(|node| {
+        let position = node
+          .position
+          .as_ref()
+          .and_then(|track| track.sample(frame))
+          .unwrap_or([0.0; 3]);
+        let rotation = node
+          .rotation
+          .as_ref()
+          .and_then(|track| track.sample(frame))
+          .unwrap_or(math::QUAT_IDENTITY);
+        let scale = node
+          .scale
+          .as_ref()
+          .and_then(|track| track.sample(frame))
+          .unwrap_or([1.0; 3]);
+        let [x, y, z] = math::quat_to_axes(rotation);
+        [
+          math::scale(x, scale[0]),
+          math::scale(y, scale[1]),
+          math::scale(z, scale[2]),
+          position,
+        ]
+      })
+      .collect();
+
+    let mut world_frames = vec![None; self.nodes.len()];
+    let mut stack: Vec<usize> = self.roots().collect();
+    while let Some(index) = stack.pop() {
+      let parent = self.nodes[index].parent.map(|parent| world_frames[parent].unwrap());
+      world_frames[index] = Some(parent.map_or(frames[index], |parent| math::multiply(&frames[index], &parent)));
+      stack.extend(&self.nodes[index].children);
+    }
+
+    self
+      .nodes
+      .iter()
+      .zip(frames.iter().zip(world_frames))
+      .map(|(node, (frame, world_frame))| {
+        let pivot: Matrix4x3 = [
+          [1.0, 0.0, 0.0],
+          [0.0, 1.0, 0.0],
+          [0.0, 0.0, 1.0],
+          math::scale(node.pivot, -1.0),
+        ];
+        NodeTransform {
+          local: math::multiply(&pivot, frame),
+          world: math::multiply(&pivot, &world_frame.unwrap()),
+        }
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::f32::consts::FRAC_PI_2;
+
+  use super::*;
+  use crate::keyframer::{Node, NodeKind};
+
+  fn key<T>(frame: u32, value: T) -> Key<T> {
+    Key {
+      frame,
+      tension: 0.0,
+      continuity: 0.0,
+      bias: 0.0,
+      ease_to: 0.0,
+      ease_from: 0.0,
+      value,
+    }
+  }
+
+  fn assert_close(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len());
+    for (actual, expected) in actual.iter().zip(expected) {
+      assert!((actual - expected).abs() < 1e-5, "{actual:?} != {expected:?}");
+    }
+  }
+
+  #[test]
+  fn position_track() {
+    let mut track = Track {
+      keys: vec![key(0, [0.0, 0.0, 0.0]), key(10, [10.0, 20.0, 0.0])],
+      ..Track::default()
+    };
+    assert_close(&track.sample(5.0).unwrap(), &[5.0, 10.0, 0.0]);
+    assert_close(&track.sample(-5.0).unwrap(), &[0.0; 3]);
+    assert_close(&track.sample(15.0).unwrap(), &[10.0, 20.0, 0.0]);
+
+    track.flags = 0x0002;
+    assert_close(&track.sample(15.0).unwrap(), &[5.0, 10.0, 0.0]);
+    assert_close(&track.sample(-2.0).unwrap(), &[8.0, 16.0, 0.0]);
+
+    track.flags = 0;
+    track.keys[0].tension = 1.0;
+    track.keys[1].tension = 1.0;
+    // Without tangents the curve eases in and out of the keys.
+    assert_close(&track.sample(2.5).unwrap(), &[1.5625, 3.125, 0.0]);
+  }
+
+  #[test]
+  fn unordered_keys() {
+    let mut track = Track {
+      keys: vec![
+        key(10, [1.0, 0.0, 0.0]),
+        key(0, [0.0; 3]),
+        key(20, [2.0, 0.0, 0.0]),
+        key(5, [3.0, 0.0, 0.0]),
+      ],
+      ..Track::default()
+    };
+    for flags in [0, 0x0002, 0x0003] {
+      track.flags = flags;
+      for frame in [-5.0, 0.0, 2.5, 7.5, 15.0, 25.0] {
+        assert!(track.sample(frame).unwrap().iter().all(|value| value.is_finite()));
+      }
+    }
+  }
+
+  #[test]
+  fn rotation_track() {
+    let track = Track {
+      keys: vec![
+        key(
+          0,
+          Rotation {
+            angle: 0.0,
+            axis: [0.0, 0.0, 1.0],
+          },
+        ),
+        key(
+          10,
+          Rotation {
+            angle: FRAC_PI_2,
+            axis: [0.0, 0.0, 1.0],
+          },
+        ),
+        key(
+          20,
+          Rotation {
+            angle: FRAC_PI_2,
+            axis: [0.0, 0.0, 1.0],
+          },
+        ),
+      ],
+      ..Track::default()
+    };
+    let x_axis = |frame| math::quat_to_axes(track.sample(frame).unwrap())[0];
+    assert_close(&x_axis(0.0), &[1.0, 0.0, 0.0]);
+    assert_close(&x_axis(5.0), &[0.5f32.sqrt(), -(0.5f32.sqrt()), 0.0]);
+    assert_close(&x_axis(10.0), &[0.0, -1.0, 0.0]);
+    assert_close(&x_axis(20.0), &[-1.0, 0.0, 0.0]);
+  }
+
+  #[test]
+  fn hierarchy() {
+    let mut parent = Node::new(NodeKind::Object, 0);
+    parent.position = Some(Track {
+      keys: vec![key(0, [10.0, 0.0, 0.0])],
+      ..Track::default()
+    });
+    let mut child = Node::new(NodeKind::Object, 1);
+    child.parent_id = Some(0);
+    child.pivot = [0.0, 1.0, 0.0];
+    child.position = Some(Track {
+      keys: vec![key(0, [1.0, 0.0, 0.0])],
+      ..Track::default()
+    });
+    child.scale = Some(Track {
+      keys: vec![key(0, [2.0; 3])],
+      ..Track::default()
+    });
+    let mut keyframer = Keyframer {
+      nodes: vec![child, parent],
+      ..Keyframer::default()
+    };
+    keyframer.link();
+
+    let transforms = keyframer.evaluate(0.0);
+    assert_close(
+      &math::transform_point(&transforms[1].world, [0.0; 3]),
+      &[10.0, 0.0, 0.0],
+    );
+    assert_close(
+      &math::transform_point(&transforms[0].local, [0.0; 3]),
+      &[1.0, -2.0, 0.0],
+    );
+    assert_close(
+      &math::transform_point(&transforms[0].world, [0.0, 1.0, 0.0]),
+      &[11.0, 0.0, 0.0],
+    );
+  }
+}
@@ -0,0 +1,53 @@
+use crate::tree::UnknownChunk;
+
+/// Width of the film frame in millimeters that 3D Studio uses to convert between lens and field of view.
+const FILM_WIDTH: f32 = 36.0;
+
+/// Camera stored in an [`OBJ_CAMERA`](crate::chunks::OBJ_CAMERA) chunk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Camera {
+  pub position: [f32; 3],
+  pub target: [f32; 3],
+  /// Bank angle around the viewing direction in degrees.
+  pub roll: f32,
+  /// Focal length in millimeters.
+  pub lens: f32,
+  pub cone_visible: bool,
+  pub near_range: Option<f32>,
+  pub far_range: Option<f32>,
+  /// Sub-chunks the parser does not understand, in file order.
+  pub unknown: Vec<UnknownChunk>,
+}
+
+impl Camera {
+  /// Returns the horizontal field of view in degrees.
+  #[must_use]
+  pub fn fov(&self) -> f32 {
+    lens_to_fov(self.lens)
+  }
+}
+
+/// Converts a focal length in millimeters into a horizontal field of view in degrees.
+#[must_use]
+pub fn lens_to_fov(lens: f32) -> f32 {
+  2.0 * (FILM_WIDTH / 2.0 / lens).atan().to_degrees()
+}
+
+/// Converts a horizontal field of view in degrees into a focal length in millimeters.
+#[must_use]
+pub fn fov_to_lens(fov: f32) -> f32 {
+  FILM_WIDTH / 2.0 / (fov / 2.0).to_radians().tan()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lens_conversion() {
+    // The default 3D Studio camera.
+    assert!((lens_to_fov(43.456) - 45.0).abs() < 1e-3);
+    assert!((fov_to_lens(45.0) - 43.456).abs() < 1e-3);
+    assert!((fov_to_lens(lens_to_fov(28.0)) - 28.0).abs() < 1e-3);
+  }
+}
@@ -40,8 +40,51 @@ pub const EDIT_UNKNW13: u16 = 0x3000;
 pub const EDIT_UNKNW14: u16 = 0xAFFF;
 
 pub const MATERIAL_NAME: u16 = 0xA000;
+pub const MATERIAL_AMBIENT: u16 = 0xA010;
+pub const MATERIAL_DIFFUSE: u16 = 0xA020;
+pub const MATERIAL_SPECULAR: u16 = 0xA030;
+pub const MATERIAL_SHININESS: u16 = 0xA040;
+pub const MATERIAL_SHININESS_STRENGTH: u16 = 0xA041;
+pub const MATERIAL_TRANSPARENCY: u16 = 0xA050;
+pub const MATERIAL_TRANSPARENCY_FALLOFF: u16 = 0xA052;
+pub const MATERIAL_REFLECTION_BLUR: u16 = 0xA053;
+pub const MATERIAL_TWO_SIDED: u16 = 0xA081;
+pub const MATERIAL_DECAL: u16 = 0xA082;
+pub const MATERIAL_ADDITIVE: u16 = 0xA083;
+pub const MATERIAL_SELF_ILLUMINATION: u16 = 0xA084;
+pub const MATERIAL_WIRE: u16 = 0xA085;
+pub const MATERIAL_WIRE_THICKNESS: u16 = 0xA087;
+pub const MATERIAL_FACE_MAP: u16 = 0xA088;
+pub const MATERIAL_SHADING: u16 = 0xA100;
 pub const MATERIAL_TEXTURE_MAP: u16 = 0xA200;
+pub const MATERIAL_SPECULAR_MAP: u16 = 0xA204;
+pub const MATERIAL_OPACITY_MAP: u16 = 0xA210;
+pub const MATERIAL_REFLECTION_MAP: u16 = 0xA220;
+pub const MATERIAL_BUMP_MAP: u16 = 0xA230;
 pub const MATERIAL_TEXTURE_MAP_NAME: u16 = 0xA300;
+pub const MATERIAL_TEXTURE_MAP2: u16 = 0xA33A;
+pub const MATERIAL_SHININESS_MAP: u16 = 0xA33C;
+pub const MATERIAL_SELF_ILLUMINATION_MAP: u16 = 0xA33D;
+pub const MATERIAL_TEXTURE_MASK: u16 = 0xA33E;
+pub const MATERIAL_TEXTURE_MASK2: u16 = 0xA340;
+pub const MATERIAL_OPACITY_MASK: u16 = 0xA342;
+pub const MATERIAL_BUMP_MASK: u16 = 0xA344;
+pub const MATERIAL_SHININESS_MASK: u16 = 0xA346;
+pub const MATERIAL_SPECULAR_MASK: u16 = 0xA348;
+pub const MATERIAL_SELF_ILLUMINATION_MASK: u16 = 0xA34A;
+pub const MATERIAL_REFLECTION_MASK: u16 = 0xA34C;
+pub const MATERIAL_TEXTURE_MAP_TILING: u16 = 0xA351;
+pub const MATERIAL_TEXTURE_MAP_BLUR: u16 = 0xA353;
+pub const MATERIAL_TEXTURE_MAP_U_SCALE: u16 = 0xA354;
+pub const MATERIAL_TEXTURE_MAP_V_SCALE: u16 = 0xA356;
+pub const MATERIAL_TEXTURE_MAP_U_OFFSET: u16 = 0xA358;
+pub const MATERIAL_TEXTURE_MAP_V_OFFSET: u16 = 0xA35A;
+pub const MATERIAL_TEXTURE_MAP_ANGLE: u16 = 0xA35C;
+pub const MATERIAL_TEXTURE_MAP_TINT1: u16 = 0xA360;
+pub const MATERIAL_TEXTURE_MAP_TINT2: u16 = 0xA362;
+pub const MATERIAL_TEXTURE_MAP_TINT_R: u16 = 0xA364;
+pub const MATERIAL_TEXTURE_MAP_TINT_G: u16 = 0xA366;
+pub const MATERIAL_TEXTURE_MAP_TINT_B: u16 = 0xA368;
 
 //>------ sub defines of EDIT_OBJECT
 pub const OBJ_TRIMESH: u16 = 0x4100;
@@ -52,33 +95,89 @@ pub const OBJ_UNKNWN01: u16 = 0x4010;
 pub const OBJ_UNKNWN02: u16 = 0x4012;
 
 //>------ sub defines of OBJ_CAMERA
-pub const CAM_UNKNWN01: u16 = 0x4710;
-pub const CAM_UNKNWN02: u16 = 0x4720;
+pub const CAM_SEE_CONE: u16 = 0x4710;
+pub const CAM_RANGES: u16 = 0x4720;
+#[deprecated(note = "use `CAM_SEE_CONE`")]
+pub const CAM_UNKNWN01: u16 = CAM_SEE_CONE;
+#[deprecated(note = "use `CAM_RANGES`")]
+pub const CAM_UNKNWN02: u16 = CAM_RANGES;
 
 //>------ sub defines of OBJ_LIGHT
 pub const LIT_OFF: u16 = 0x4620;
 pub const LIT_SPOT: u16 = 0x4610;
-pub const LIT_UNKNWN01: u16 = 0x465A;
+pub const LIT_ATTENUATE: u16 = 0x4625;
+pub const LIT_INNER_RANGE: u16 = 0x4659;
+pub const LIT_OUTER_RANGE: u16 = 0x465A;
+#[deprecated(note = "use `LIT_OUTER_RANGE`")]
+pub const LIT_UNKNWN01: u16 = LIT_OUTER_RANGE;
+pub const LIT_MULTIPLIER: u16 = 0x465B;
+
+//>------ sub defines of LIT_SPOT
+pub const LIT_SHADOWED: u16 = 0x4630;
+pub const LIT_LOCAL_SHADOW: u16 = 0x4641;
+pub const LIT_SEE_CONE: u16 = 0x4650;
+pub const LIT_SPOT_RECTANGULAR: u16 = 0x4651;
+pub const LIT_SPOT_OVERSHOOT: u16 = 0x4652;
+pub const LIT_SPOT_ROLL: u16 = 0x4656;
+pub const LIT_SPOT_ASPECT: u16 = 0x4657;
 
 //>------ sub defines of OBJ_TRIMESH
 pub const TRI_VERTEXL: u16 = 0x4110;
 pub const TRI_FACEL2: u16 = 0x4111;
 pub const TRI_FACEL1: u16 = 0x4120;
+pub const TRI_MATERIAL: u16 = 0x4130;
+pub const TRI_MAPPINGCOORS: u16 = 0x4140;
 pub const TRI_SMOOTH: u16 = 0x4150;
 pub const TRI_LOCAL: u16 = 0x4160;
 pub const TRI_VISIBLE: u16 = 0x4165;
 
 //>>------ sub defs of KEYF3DS
 
-pub const KEYF_UNKNWN01: u16 = 0xB009;
-pub const KEYF_UNKNWN02: u16 = 0xB00A;
 pub const KEYF_FRAMES: u16 = 0xB008;
+pub const KEYF_CURTIME: u16 = 0xB009;
+pub const KEYF_HDR: u16 = 0xB00A;
+#[deprecated(note = "use `KEYF_CURTIME`")]
+pub const KEYF_UNKNWN01: u16 = KEYF_CURTIME;
+#[deprecated(note = "use `KEYF_HDR`")]
+pub const KEYF_UNKNWN02: u16 = KEYF_HDR;
 pub const KEYF_OBJDES: u16 = 0xB002;
 
+pub const KEYF_AMBIENT: u16 = 0xB001;
+pub const KEYF_CAMERA: u16 = 0xB003;
+pub const KEYF_CAMERA_TARGET: u16 = 0xB004;
+pub const KEYF_LIGHT: u16 = 0xB005;
+pub const KEYF_LIGHT_TARGET: u16 = 0xB006;
+pub const KEYF_SPOTLIGHT: u16 = 0xB007;
+
+//>>------ sub defines of keyframer nodes
+
+pub const NODE_HDR: u16 = 0xB010;
+pub const NODE_INSTANCE_NAME: u16 = 0xB011;
+pub const NODE_PIVOT: u16 = 0xB013;
+pub const NODE_BOUNDBOX: u16 = 0xB014;
+pub const NODE_POS_TRACK: u16 = 0xB020;
+pub const NODE_ROT_TRACK: u16 = 0xB021;
+pub const NODE_SCL_TRACK: u16 = 0xB022;
+pub const NODE_FOV_TRACK: u16 = 0xB023;
+pub const NODE_ROLL_TRACK: u16 = 0xB024;
+pub const NODE_COL_TRACK: u16 = 0xB025;
+pub const NODE_MORPH_TRACK: u16 = 0xB026;
+pub const NODE_HOT_TRACK: u16 = 0xB027;
+pub const NODE_FALL_TRACK: u16 = 0xB028;
+pub const NODE_HIDE_TRACK: u16 = 0xB029;
+pub const NODE_ID: u16 = 0xB030;
+
 //>>------  these define the different color chunk types
 pub const COL_RGB: u16 = 0x0010;
 pub const COL_TRU: u16 = 0x0011;
-pub const COL_UNK: u16 = 0x0013;
+pub const COL_TRU_GAMMA: u16 = 0x0012;
+pub const COL_RGB_GAMMA: u16 = 0x0013;
+#[deprecated(note = "use `COL_RGB_GAMMA`")]
+pub const COL_UNK: u16 = COL_RGB_GAMMA;
+
+//>>------  these define the different percentage chunk types
+pub const PCT_INT: u16 = 0x0030;
+pub const PCT_FLOAT: u16 = 0x0031;
 
 //>>------ defines for viewport chunks
 
@@ -93,3 +192,197 @@ pub const CAMERA: u16 = 0x0008;
 pub const LIGHT: u16 = 0x0009;
 pub const DISABLED: u16 = 0x0010;
 pub const BOGUS: u16 = 0x0011;
+
+/// Returns the name of the constant defining the given chunk id, if it is known.
+#[must_use]
+#[allow(clippy::too_many_lines)]
+pub const fn name(id: u16) -> Option<&'static str> {
+  Some(match id {
+    MAIN3DS => "MAIN3DS",
+    MAIN_VERSION => "MAIN_VERSION",
+    MAIN_EDITOR => "MAIN_EDITOR",
+    MAIN_KEYFRAMES => "MAIN_KEYFRAMES",
+    EDIT_VERSION => "EDIT_VERSION",
+    EDIT_MATERIAL => "EDIT_MATERIAL",
+    EDIT_CONFIG1 => "EDIT_CONFIG1",
+    EDIT_CONFIG2 => "EDIT_CONFIG2",
+    EDIT_VIEW_P1 => "EDIT_VIEW_P1",
+    EDIT_VIEW_P2 => "EDIT_VIEW_P2",
+    EDIT_VIEW_P3 => "EDIT_VIEW_P3",
+    EDIT_VIEW1 => "EDIT_VIEW1",
+    EDIT_BACKGR => "EDIT_BACKGR",
+    EDIT_AMBIENT => "EDIT_AMBIENT",
+    EDIT_OBJECT => "EDIT_OBJECT",
+    EDIT_UNKNW01 => "EDIT_UNKNW01",
+    EDIT_UNKNW02 => "EDIT_UNKNW02",
+    EDIT_UNKNW03 => "EDIT_UNKNW03",
+    EDIT_UNKNW04 => "EDIT_UNKNW04",
+    EDIT_UNKNW05 => "EDIT_UNKNW05",
+    EDIT_UNKNW06 => "EDIT_UNKNW06",
+    EDIT_UNKNW07 => "EDIT_UNKNW07",
+    EDIT_UNKNW08 => "EDIT_UNKNW08",
+    EDIT_UNKNW09 => "EDIT_UNKNW09",
+    EDIT_UNKNW10 => "EDIT_UNKNW10",
+    EDIT_UNKNW11 => "EDIT_UNKNW11",
+    EDIT_UNKNW12 => "EDIT_UNKNW12",
+    EDIT_UNKNW13 => "EDIT_UNKNW13",
+    MATERIAL_NAME => "MATERIAL_NAME",
+    MATERIAL_AMBIENT => "MATERIAL_AMBIENT",
+    MATERIAL_DIFFUSE => "MATERIAL_DIFFUSE",
+    MATERIAL_SPECULAR => "MATERIAL_SPECULAR",
+    MATERIAL_SHININESS => "MATERIAL_SHININESS",
+    MATERIAL_SHININESS_STRENGTH => "MATERIAL_SHININESS_STRENGTH",
+    MATERIAL_TRANSPARENCY => "MATERIAL_TRANSPARENCY",
+    MATERIAL_TRANSPARENCY_FALLOFF => "MATERIAL_TRANSPARENCY_FALLOFF",
+    MATERIAL_REFLECTION_BLUR => "MATERIAL_REFLECTION_BLUR",
+    MATERIAL_TWO_SIDED => "MATERIAL_TWO_SIDED",
+    MATERIAL_DECAL => "MATERIAL_DECAL",
+    MATERIAL_ADDITIVE => "MATERIAL_ADDITIVE",
+    MATERIAL_SELF_ILLUMINATION => "MATERIAL_SELF_ILLUMINATION",
+    MATERIAL_WIRE => "MATERIAL_WIRE",
+    MATERIAL_WIRE_THICKNESS => "MATERIAL_WIRE_THICKNESS",
+    MATERIAL_FACE_MAP => "MATERIAL_FACE_MAP",
+    MATERIAL_SHADING => "MATERIAL_SHADING",
+    MATERIAL_TEXTURE_MAP => "MATERIAL_TEXTURE_MAP",
+    MATERIAL_SPECULAR_MAP => "MATERIAL_SPECULAR_MAP",
+    MATERIAL_OPACITY_MAP => "MATERIAL_OPACITY_MAP",
+    MATERIAL_REFLECTION_MAP => "MATERIAL_REFLECTION_MAP",
+    MATERIAL_BUMP_MAP => "MATERIAL_BUMP_MAP",
+    MATERIAL_TEXTURE_MAP_NAME => "MATERIAL_TEXTURE_MAP_NAME",
+    MATERIAL_TEXTURE_MAP2 => "MATERIAL_TEXTURE_MAP2",
+    MATERIAL_SHININESS_MAP => "MATERIAL_SHININESS_MAP",
+    MATERIAL_SELF_ILLUMINATION_MAP => "MATERIAL_SELF_ILLUMINATION_MAP",
+    MATERIAL_TEXTURE_MASK => "MATERIAL_TEXTURE_MASK",
+    MATERIAL_TEXTURE_MASK2 => "MATERIAL_TEXTURE_MASK2",
+    MATERIAL_OPACITY_MASK => "MATERIAL_OPACITY_MASK",
+    MATERIAL_BUMP_MASK => "MATERIAL_BUMP_MASK",
+    MATERIAL_SHININESS_MASK => "MATERIAL_SHININESS_MASK",
+    MATERIAL_SPECULAR_MASK => "MATERIAL_SPECULAR_MASK",
+    MATERIAL_SELF_ILLUMINATION_MASK => "MATERIAL_SELF_ILLUMINATION_MASK",
+    MATERIAL_REFLECTION_MASK => "MATERIAL_REFLECTION_MASK",
+    MATERIAL_TEXTURE_MAP_TILING => "MATERIAL_TEXTURE_MAP_TILING",
+    MATERIAL_TEXTURE_MAP_BLUR => "MATERIAL_TEXTURE_MAP_BLUR",
+    MATERIAL_TEXTURE_MAP_U_SCALE => "MATERIAL_TEXTURE_MAP_U_SCALE",
+    MATERIAL_TEXTURE_MAP_V_SCALE => "MATERIAL_TEXTURE_MAP_V_SCALE",
+    MATERIAL_TEXTURE_MAP_U_OFFSET => "MATERIAL_TEXTURE_MAP_U_OFFSET",
+    MATERIAL_TEXTURE_MAP_V_OFFSET => "MATERIAL_TEXTURE_MAP_V_OFFSET",
+    MATERIAL_TEXTURE_MAP_ANGLE => "MATERIAL_TEXTURE_MAP_ANGLE",
+    MATERIAL_TEXTURE_MAP_TINT1 => "MATERIAL_TEXTURE_MAP_TINT1",
+    MATERIAL_TEXTURE_MAP_TINT2 => "MATERIAL_TEXTURE_MAP_TINT2",
+    MATERIAL_TEXTURE_MAP_TINT_R => "MATERIAL_TEXTURE_MAP_TINT_R",
+    MATERIAL_TEXTURE_MAP_TINT_G => "MATERIAL_TEXTURE_MAP_TINT_G",
+    MATERIAL_TEXTURE_MAP_TINT_B => "MATERIAL_TEXTURE_MAP_TINT_B",
+    OBJ_TRIMESH => "OBJ_TRIMESH",
+    OBJ_LIGHT => "OBJ_LIGHT",
+    OBJ_CAMERA => "OBJ_CAMERA",
+    OBJ_UNKNWN01 => "OBJ_UNKNWN01",
+    OBJ_UNKNWN02 => "OBJ_UNKNWN02",
+    CAM_SEE_CONE => "CAM_SEE_CONE",
+    CAM_RANGES => "CAM_RANGES",
+    LIT_OFF => "LIT_OFF",
+    LIT_SPOT => "LIT_SPOT",
+    LIT_ATTENUATE => "LIT_ATTENUATE",
+    LIT_INNER_RANGE => "LIT_INNER_RANGE",
+    LIT_OUTER_RANGE => "LIT_OUTER_RANGE",
+    LIT_MULTIPLIER => "LIT_MULTIPLIER",
+    LIT_SHADOWED => "LIT_SHADOWED",
+    LIT_LOCAL_SHADOW => "LIT_LOCAL_SHADOW",
+    LIT_SEE_CONE => "LIT_SEE_CONE",
+    LIT_SPOT_RECTANGULAR => "LIT_SPOT_RECTANGULAR",
+    LIT_SPOT_OVERSHOOT => "LIT_SPOT_OVERSHOOT",
+    LIT_SPOT_ROLL => "LIT_SPOT_ROLL",
+    LIT_SPOT_ASPECT => "LIT_SPOT_ASPECT",
+    TRI_VERTEXL => "TRI_VERTEXL",
+    TRI_FACEL2 => "TRI_FACEL2",
+    TRI_FACEL1 => "TRI_FACEL1",
+    TRI_MATERIAL => "TRI_MATERIAL",
+    TRI_MAPPINGCOORS => "TRI_MAPPINGCOORS",
+    TRI_SMOOTH => "TRI_SMOOTH",
+    TRI_LOCAL => "TRI_LOCAL",
+    TRI_VISIBLE => "TRI_VISIBLE",
+    KEYF_CURTIME => "KEYF_CURTIME",
+    KEYF_HDR => "KEYF_HDR",
+    KEYF_FRAMES => "KEYF_FRAMES",
+    KEYF_OBJDES => "KEYF_OBJDES",
+    KEYF_AMBIENT => "KEYF_AMBIENT",
+    KEYF_CAMERA => "KEYF_CAMERA",
+    KEYF_CAMERA_TARGET => "KEYF_CAMERA_TARGET",
+    KEYF_LIGHT => "KEYF_LIGHT",
+    KEYF_LIGHT_TARGET => "KEYF_LIGHT_TARGET",
+    KEYF_SPOTLIGHT => "KEYF_SPOTLIGHT",
+    NODE_HDR => "NODE_HDR",
+    NODE_INSTANCE_NAME => "NODE_INSTANCE_NAME",
+    NODE_PIVOT => "NODE_PIVOT",
+    NODE_BOUNDBOX => "NODE_BOUNDBOX",
+    NODE_POS_TRACK => "NODE_POS_TRACK",
+    NODE_ROT_TRACK => "NODE_ROT_TRACK",
+    NODE_SCL_TRACK => "NODE_SCL_TRACK",
+    NODE_FOV_TRACK => "NODE_FOV_TRACK",
+    NODE_ROLL_TRACK => "NODE_ROLL_TRACK",
+    NODE_COL_TRACK => "NODE_COL_TRACK",
+    NODE_MORPH_TRACK => "NODE_MORPH_TRACK",
+    NODE_HOT_TRACK => "NODE_HOT_TRACK",
+    NODE_FALL_TRACK => "NODE_FALL_TRACK",
+    NODE_HIDE_TRACK => "NODE_HIDE_TRACK",
+    NODE_ID => "NODE_ID",
+    COL_RGB => "COL_RGB",
+    COL_TRU => "COL_TRU",
+    COL_TRU_GAMMA => "COL_TRU_GAMMA",
+    COL_RGB_GAMMA => "COL_RGB_GAMMA",
+    PCT_INT => "PCT_INT",
+    PCT_FLOAT => "PCT_FLOAT",
+    _ => return None,
+  })
+}
+
+/// Returns whether chunks with the given id contain sub-chunks, possibly after some data of their own.
+#[must_use]
+pub const fn is_container(id: u16) -> bool {
+  matches!(
+    id,
+    MAIN3DS
+      | MAIN_EDITOR
+      | MAIN_KEYFRAMES
+      | EDIT_MATERIAL
+      | EDIT_BACKGR
+      | EDIT_AMBIENT
+      | EDIT_OBJECT
+      | MATERIAL_AMBIENT
+      | MATERIAL_DIFFUSE
+      | MATERIAL_SPECULAR
+      | MATERIAL_SHININESS
+      | MATERIAL_SHININESS_STRENGTH
+      | MATERIAL_TRANSPARENCY
+      | MATERIAL_TRANSPARENCY_FALLOFF
+      | MATERIAL_REFLECTION_BLUR
+      | MATERIAL_SELF_ILLUMINATION
+      | MATERIAL_TEXTURE_MAP
+      | MATERIAL_SPECULAR_MAP
+      | MATERIAL_OPACITY_MAP
+      | MATERIAL_REFLECTION_MAP
+      | MATERIAL_BUMP_MAP
+      | MATERIAL_TEXTURE_MAP2
+      | MATERIAL_SHININESS_MAP
+      | MATERIAL_SELF_ILLUMINATION_MAP
+      | MATERIAL_TEXTURE_MASK
+      | MATERIAL_TEXTURE_MASK2
+      | MATERIAL_OPACITY_MASK
+      | MATERIAL_BUMP_MASK
+      | MATERIAL_SHININESS_MASK
+      | MATERIAL_SPECULAR_MASK
+      | MATERIAL_SELF_ILLUMINATION_MASK
+      | MATERIAL_REFLECTION_MASK
+      | OBJ_TRIMESH
+      | OBJ_LIGHT
+      | OBJ_CAMERA
+      | LIT_SPOT
+      | TRI_FACEL1
+      | KEYF_AMBIENT
+      | KEYF_OBJDES
+      | KEYF_CAMERA
+      | KEYF_CAMERA_TARGET
+      | KEYF_LIGHT
+      | KEYF_LIGHT_TARGET
+      | KEYF_SPOTLIGHT
+  )
+}
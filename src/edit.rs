@@ -0,0 +1,242 @@
+//! Editing of names and paths in a [`ChunkTree`] without touching the rest of the file.
+//!
+//! Edited trees are written back with [`Writer3DS::write_chunk_tree`](crate::Writer3DS::write_chunk_tree), which
+//! recomputes the lengths of all chunks.
+
+use crate::chunks::{EDIT_MATERIAL, MAIN_EDITOR, MATERIAL_NAME, MATERIAL_TEXTURE_MAP_NAME, TRI_MATERIAL};
+use crate::tree::{ChunkTree, RawChunk};
+use crate::{Error, ErrorKind, Result};
+
+impl RawChunk {
+  /// Calls the function for the chunk and all of its descendants in file order.
+  pub fn for_each_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+    f(self);
+    for child in &mut self.children {
+      child.for_each_mut(f);
+    }
+  }
+
+  /// Returns the null-terminated string the payload starts with, if it is valid UTF-8.
+  #[must_use]
+  pub fn leading_string(&self) -> Option<&str> {
+    let length = self.data.iter().position(|&byte| byte == b'\0')?;
+    std::str::from_utf8(&self.data[..length]).ok()
+  }
+
+  /// Replaces the null-terminated string the payload starts with, keeping the bytes after it.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the new string contains a null byte or the payload does not start with a null-terminated
+  /// string.
+  pub fn set_leading_string(&mut self, value: &str) -> Result<()> {
+    let length = self.leading_string_length(value)?;
+    self.data.splice(..length, value.bytes());
+    Ok(())
+  }
+
+  /// Returns the length of the leading string, checking that the given value can replace it.
+  fn leading_string_length(&self, value: &str) -> Result<usize> {
+    if value.contains('\0') {
+      return Err(self.error(ErrorKind::NulInString(value.to_owned())));
+    }
+    self
+      .data
+      .iter()
+      .position(|&byte| byte == b'\0')
+      .ok_or_else(|| self.error(ErrorKind::MissingString))
+  }
+
+  const fn error(&self, kind: ErrorKind) -> Error {
+    Error::new(kind, self.offset, Vec::new())
+  }
+}
+
+impl ChunkTree {
+  /// Returns the names of all materials in the order they are defined.
+  #[must_use]
+  pub fn material_names(&self) -> Vec<&str> {
+    self
+      .material_name_chunks()
+      .filter_map(RawChunk::leading_string)
+      .collect()
+  }
+
+  fn material_name_chunks(&self) -> impl Iterator<Item = &RawChunk> {
+    self
+      .root
+      .find(MAIN_EDITOR)
+      .into_iter()
+      .flat_map(|editor| editor.children.iter().filter(|chunk| chunk.id == EDIT_MATERIAL))
+      .filter_map(|material| material.find(MATERIAL_NAME))
+  }
+
+  /// Renames a material, together with the face groups of the meshes that use it.
+  ///
+  /// Returns the number of chunks that were changed. If no material has the given name, nothing is changed and zero is
+  /// returned, even if some face groups refer to that name.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if another material already has the new name or the new name contains a null byte. The tree
+  /// is left unchanged in that case.
+  pub fn rename_material(&mut self, from: &str, to: &str) -> Result<usize> {
+    if !self.material_names().contains(&from) {
+      return Ok(0);
+    }
+    if from != to {
+      if let Some(existing) = self
+        .material_name_chunks()
+        .find(|chunk| chunk.leading_string() == Some(to))
+      {
+        return Err(existing.error(ErrorKind::DuplicateMaterialName(to.to_owned())));
+      }
+    }
+    self.rewrite_leading_strings(&[MATERIAL_NAME, TRI_MATERIAL], |name| {
+      (name == from).then(|| to.to_owned())
+    })
+  }
+
+  /// Rewrites the file names of all texture maps and masks of all materials.
+  ///
+  /// The function gets the current file name and returns the new one, or `None` to keep it. Returns the number of
+  /// file names that were changed.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if a new file name contains a null byte. The tree is left unchanged in that case.
+  pub fn rewrite_texture_paths(&mut self, rewrite: impl FnMut(&str) -> Option<String>) -> Result<usize> {
+    self.rewrite_leading_strings(&[MATERIAL_TEXTURE_MAP_NAME], rewrite)
+  }
+
+  /// Replaces the leading strings of all chunks with one of the given ids for which the function returns a new one.
+  ///
+  /// All new strings are checked before the first one is stored, so that an error leaves the tree unchanged.
+  fn rewrite_leading_strings(&mut self, ids: &[u16], mut rewrite: impl FnMut(&str) -> Option<String>) -> Result<usize> {
+    let mut replacements = Vec::new();
+    for chunk in self.root.descendants().filter(|chunk| ids.contains(&chunk.id)) {
+      let replacement = match chunk.leading_string().and_then(&mut rewrite) {
+        Some(value) => Some((chunk.leading_string_length(&value)?, value)),
+        None => None,
+      };
+      replacements.push(replacement);
+    }
+    let count = replacements.iter().flatten().count();
+
+    let mut replacements = replacements.into_iter();
+    self.root.for_each_mut(&mut |chunk| {
+      if !ids.contains(&chunk.id) {
+        return;
+      }
+      if let Some(Some((length, value))) = replacements.next() {
+        chunk.data.splice(..length, value.bytes());
+      }
+    });
+    Ok(count)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+  use std::io::Cursor;
+
+  use crate::chunks::{MAIN_EDITOR, MATERIAL_NAME};
+  use crate::{Editor, ErrorKind, Main, Material, MaterialTextureMap, ObjectKind, Parser3DS, Writer3DS};
+
+  fn read_main(data: &[u8]) -> Vec<Main> {
+    Parser3DS::from_bytes(data).read_main().unwrap()
+  }
+
+  #[test]
+  fn rename_and_rewrite() {
+    let data = fs::read("test/tower.3ds").unwrap();
+    let mut tree = Parser3DS::from_bytes(data.as_slice()).read_chunk_tree().unwrap();
+    assert_eq!(tree.material_names(), ["Material #441", "Default"]);
+
+    assert_eq!(tree.rename_material("Missing", "Other").unwrap(), 0);
+    assert_eq!(tree.rename_material("Missing", "Default").unwrap(), 0);
+    assert_eq!(tree.rename_material("Material #441", "Stone").unwrap(), 2);
+    let count = tree.rewrite_texture_paths(|path| {
+      let stem = path.strip_suffix(".JPG")?;
+      Some(format!("textures/{}.png", stem.to_lowercase()))
+    });
+    assert_eq!(count.unwrap(), 1);
+    assert_eq!(tree.material_names(), ["Stone", "Default"]);
+
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_chunk_tree(&tree).unwrap();
+    let written = writer.into_inner().into_inner();
+
+    // Apply the same edits to the original file to check that nothing else changed.
+    let mut expected = read_main(&data);
+    for item in &mut expected {
+      let Main::Editor(editor) = item else {
+        continue;
+      };
+      for item in editor {
+        match item {
+          Editor::Material(material) => {
+            for property in material {
+              match property {
+                Material::Name(name) if name == "Material #441" => *name = "Stone".to_owned(),
+                Material::TextureMap(map) => {
+                  for parameter in map {
+                    if let MaterialTextureMap::Name(name) = parameter {
+                      *name = "textures/tower_ne.png".to_owned();
+                    }
+                  }
+                }
+                _ => {}
+              }
+            }
+          }
+          Editor::Object {
+            kind: ObjectKind::TriMesh(mesh),
+            ..
+          } => {
+            for group in &mut mesh.material_groups {
+              if group.material_name == "Material #441" {
+                group.material_name = "Stone".to_owned();
+              }
+            }
+          }
+          _ => {}
+        }
+      }
+    }
+    assert_eq!(read_main(&written), expected);
+  }
+
+  #[test]
+  fn rejected_edits() {
+    let data = fs::read("test/tower.3ds").unwrap();
+    let mut cursor = Cursor::new(data.as_slice());
+    let mut tree = Parser3DS::new(&mut cursor).read_chunk_tree().unwrap();
+    let original = tree.clone();
+
+    let error = tree.rename_material("Material #441", "Default").unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::DuplicateMaterialName(name) if name == "Default"));
+    let error = tree.rename_material("Material #441", "Sto\0ne").unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::NulInString(_)));
+    let error = tree.rewrite_texture_paths(|_| Some("a\0b".to_owned())).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::NulInString(_)));
+    assert_eq!(tree, original);
+
+    // Face groups referring to a material that does not exist are not renamed.
+    tree.root.for_each_mut(&mut |chunk| {
+      if chunk.id == MATERIAL_NAME {
+        chunk.data = b"Unused\0".to_vec();
+      }
+    });
+    let original = tree.clone();
+    assert_eq!(tree.rename_material("Material #441", "Stone").unwrap(), 0);
+    assert_eq!(tree, original);
+
+    let mut chunk = tree.root.find(MAIN_EDITOR).unwrap().clone();
+    chunk.data = vec![1, 2, 3];
+    let error = chunk.set_leading_string("name").unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::MissingString));
+    assert_eq!(error.offset(), chunk.offset);
+  }
+}
@@ -0,0 +1,141 @@
+use std::fmt::{self, Display};
+use std::io;
+use std::string::FromUtf8Error;
+
+use crate::chunks;
+use crate::ChunkInfo;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// An error that occurred while parsing, together with the location in the file where it happened.
+#[derive(Debug)]
+pub struct Error {
+  kind: ErrorKind,
+  offset: u64,
+  path: Vec<ChunkInfo>,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+  /// The underlying reader failed.
+  Io(io::Error),
+  /// The input ended in the middle of a chunk.
+  UnexpectedEof,
+  /// A chunk header declares a length that is shorter than the header itself or extends past the end of the input.
+  BadChunkLength(u32),
+  /// The file does not start with a [`MAIN3DS`](crate::chunks::MAIN3DS) chunk.
+  UnknownRootChunk(u16),
+  /// A string is not valid UTF-8.
+  InvalidString(FromUtf8Error),
+  /// A mesh has a different number of texture coordinates than vertices.
+  MappingCountMismatch { vertices: usize, uvs: usize },
+  /// A face of a mesh refers to a vertex that does not exist.
+  FaceIndexOutOfRange { index: u16, vertices: usize },
+  /// A list has more elements than the 16-bit count it is written with can hold.
+  CountTooLarge(usize),
+  /// A chunk is longer than its 32-bit header can describe.
+  ChunkTooLarge(u64),
+  /// A string to be stored in a chunk contains a null byte.
+  NulInString(String),
+  /// A chunk that should start with a null-terminated string does not.
+  MissingString,
+  /// A material cannot be renamed because another material already has the new name.
+  DuplicateMaterialName(String),
+  /// Chunks are nested deeper than any real file does.
+  NestingTooDeep,
+}
+
+impl Error {
+  pub(crate) const fn new(kind: ErrorKind, offset: u64, path: Vec<ChunkInfo>) -> Self {
+    Self { kind, offset, path }
+  }
+
+  #[must_use]
+  pub const fn kind(&self) -> &ErrorKind {
+    &self.kind
+  }
+
+  /// Byte offset at which the error was detected, relative to where the input was when the parser was created.
+  #[must_use]
+  pub const fn offset(&self) -> u64 {
+    self.offset
+  }
+
+  /// Id of the innermost chunk that was being read, if any.
+  #[must_use]
+  pub fn chunk_id(&self) -> Option<u16> {
+    self.path.last().map(ChunkInfo::get_id)
+  }
+
+  /// Chain of chunks from the root down to the one that was being read.
+  #[must_use]
+  pub fn path(&self) -> &[ChunkInfo] {
+    &self.path
+  }
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at offset 0x{:x}", self.kind, self.offset)?;
+    for (index, info) in self.path.iter().enumerate() {
+      f.write_str(if index == 0 { " in " } else { " > " })?;
+      match chunks::name(info.get_id()) {
+        Some(name) => f.write_str(name)?,
+        None => write!(f, "0x{:04x}", info.get_id())?,
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl Display for ErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Io(error) => write!(f, "I/O error: {error}"),
+      Self::UnexpectedEof => write!(f, "unexpected end of file"),
+      Self::BadChunkLength(length) => write!(f, "bad chunk length 0x{length:x}"),
+      Self::UnknownRootChunk(id) => write!(f, "unknown root chunk 0x{id:04x}"),
+      Self::InvalidString(error) => write!(f, "invalid string: {error}"),
+      Self::MappingCountMismatch { vertices, uvs } => {
+        write!(f, "mesh has {uvs} texture coordinates for {vertices} vertices")
+      }
+      Self::FaceIndexOutOfRange { index, vertices } => {
+        write!(f, "face refers to vertex {index} of a mesh with {vertices} vertices")
+      }
+      Self::CountTooLarge(count) => write!(f, "{count} elements do not fit into a 16-bit count"),
+      Self::ChunkTooLarge(length) => write!(f, "chunk length 0x{length:x} does not fit into 32 bits"),
+      Self::NulInString(value) => write!(f, "string {value:?} contains a null byte"),
+      Self::MissingString => write!(f, "chunk does not start with a null-terminated string"),
+      Self::DuplicateMaterialName(name) => write!(f, "a material named {name:?} already exists"),
+      Self::NestingTooDeep => write!(f, "chunks are nested too deeply"),
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match &self.kind {
+      ErrorKind::Io(error) => Some(error),
+      ErrorKind::InvalidString(error) => Some(error),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for ErrorKind {
+  fn from(error: io::Error) -> Self {
+    if error.kind() == io::ErrorKind::UnexpectedEof {
+      Self::UnexpectedEof
+    } else {
+      Self::Io(error)
+    }
+  }
+}
+
+impl From<FromUtf8Error> for ErrorKind {
+  fn from(error: FromUtf8Error) -> Self {
+    Self::InvalidString(error)
+  }
+}
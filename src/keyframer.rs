@@ -0,0 +1,234 @@
+use std::ops::RangeInclusive;
+
+use crate::tree::UnknownChunk;
+
+/// Animation data stored in a [`MAIN_KEYFRAMES`](crate::chunks::MAIN_KEYFRAMES) chunk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Keyframer {
+  pub revision: u16,
+  /// Name of the file the keyframer data was saved from.
+  pub filename: String,
+  /// Length of the whole animation in frames.
+  pub length: u32,
+  /// First and last frame of the active segment, which is the part of the animation that is played and rendered.
+  pub segment: Option<RangeInclusive<u32>>,
+  pub current_frame: u32,
+  /// Chunks between the nodes the parser does not understand, in file order.
+  pub unknown: Vec<UnknownChunk>,
+  /// Nodes in the order they are stored in the file, linked into a tree through [`Node::parent`] and
+  /// [`Node::children`].
+  pub nodes: Vec<Node>,
+}
+
+/// Kind of scene element a keyframer node animates, given by the tag of its chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+  Ambient,
+  Object,
+  Camera,
+  CameraTarget,
+  Light,
+  LightTarget,
+  Spotlight,
+}
+
+/// Node of the keyframer hierarchy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+  pub kind: NodeKind,
+  /// Id other nodes use to refer to this node as their parent.
+  pub id: u16,
+  /// Name of the object, camera or light this node animates.
+  pub name: String,
+  pub flags: [u16; 2],
+  /// Id of the parent node as stored in the file, [`None`] for root nodes.
+  pub parent_id: Option<u16>,
+  /// Name of this particular instance when the same object is used by several nodes.
+  pub instance_name: Option<String>,
+  pub pivot: [f32; 3],
+  /// Minimum and maximum corners of the bounding box of the object.
+  pub bounding_box: Option<[[f32; 3]; 2]>,
+  /// Index of the parent node in [`Keyframer::nodes`].
+  pub parent: Option<usize>,
+  /// Indices of the child nodes in [`Keyframer::nodes`].
+  pub children: Vec<usize>,
+  pub position: Option<Track<[f32; 3]>>,
+  pub rotation: Option<Track<Rotation>>,
+  pub scale: Option<Track<[f32; 3]>>,
+  /// Field of view of a camera in degrees.
+  pub fov: Option<Track<f32>>,
+  /// Roll of a camera or spotlight in degrees.
+  pub roll: Option<Track<f32>>,
+  pub color: Option<Track<[f32; 3]>>,
+  /// Name of the object the mesh is morphed into.
+  pub morph: Option<Track<String>>,
+  /// Hotspot angle of a spotlight in degrees.
+  pub hotspot: Option<Track<f32>>,
+  /// Falloff angle of a spotlight in degrees.
+  pub falloff: Option<Track<f32>>,
+  /// Each key toggles the visibility of the object.
+  pub hide: Option<Track<()>>,
+  /// Sub-chunks the parser does not understand, in file order.
+  pub unknown: Vec<UnknownChunk>,
+}
+
+/// Animation track of a keyframer node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Track<T> {
+  pub flags: u16,
+  /// Two words following the flags in the track header whose meaning is unknown, kept so that they are written back.
+  pub reserved: [u32; 2],
+  pub keys: Vec<Key<T>>,
+}
+
+impl<T> Track<T> {
+  const MODE_MASK: u16 = 0x0003;
+  const MODE_REPEAT: u16 = 0x0002;
+  const MODE_LOOP: u16 = 0x0003;
+
+  /// Whether the track jumps back to its first key after the last one.
+  #[must_use]
+  pub const fn is_repeat(&self) -> bool {
+    self.flags & Self::MODE_MASK == Self::MODE_REPEAT
+  }
+
+  /// Whether the track interpolates from its last key back to the first one.
+  #[must_use]
+  pub const fn is_loop(&self) -> bool {
+    self.flags & Self::MODE_MASK == Self::MODE_LOOP
+  }
+}
+
+/// Key of an animation track with its Kochanek-Bartels spline parameters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Key<T> {
+  pub frame: u32,
+  pub tension: f32,
+  pub continuity: f32,
+  pub bias: f32,
+  pub ease_to: f32,
+  pub ease_from: f32,
+  pub value: T,
+}
+
+/// Rotation key value, relative to the previous key of the track.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rotation {
+  /// Angle in radians.
+  pub angle: f32,
+  pub axis: [f32; 3],
+}
+
+impl Node {
+  #[must_use]
+  pub const fn new(kind: NodeKind, id: u16) -> Self {
+    Self {
+      kind,
+      id,
+      name: String::new(),
+      flags: [0; 2],
+      parent_id: None,
+      instance_name: None,
+      pivot: [0.0; 3],
+      bounding_box: None,
+      parent: None,
+      children: Vec::new(),
+      position: None,
+      rotation: None,
+      scale: None,
+      fov: None,
+      roll: None,
+      color: None,
+      morph: None,
+      hotspot: None,
+      falloff: None,
+      hide: None,
+      unknown: Vec::new(),
+    }
+  }
+}
+
+impl Keyframer {
+  /// Resolves the parent ids of all nodes into [`Node::parent`] and [`Node::children`].
+  ///
+  /// Nodes whose parent does not exist are treated as roots.
+  pub fn link(&mut self) {
+    for node in &mut self.nodes {
+      node.parent = None;
+      node.children.clear();
+    }
+
+    for index in 0..self.nodes.len() {
+      let Some(parent_id) = self.nodes[index].parent_id else {
+        continue;
+      };
+      if let Some(parent) = self.nodes.iter().position(|node| node.id == parent_id) {
+        if !self.is_ancestor(index, parent) {
+          self.nodes[index].parent = Some(parent);
+          self.nodes[parent].children.push(index);
+        }
+      }
+    }
+  }
+
+  /// Returns whether `ancestor` is `node` or one of its already linked ancestors.
+  fn is_ancestor(&self, ancestor: usize, node: usize) -> bool {
+    let mut current = Some(node);
+    while let Some(index) = current {
+      if index == ancestor {
+        return true;
+      }
+      current = self.nodes[index].parent;
+    }
+
+    false
+  }
+
+  /// Returns the indices of the nodes without a parent.
+  pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+    (0..self.nodes.len()).filter(|&index| self.nodes[index].parent.is_none())
+  }
+
+  /// Finds the index of the first node animating the object with the given name.
+  #[must_use]
+  pub fn find(&self, name: &str) -> Option<usize> {
+    self.nodes.iter().position(|node| node.name == name)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn node(id: u16, parent_id: Option<u16>) -> Node {
+    Node {
+      parent_id,
+      ..Node::new(NodeKind::Object, id)
+    }
+  }
+
+  #[test]
+  fn link() {
+    let mut keyframer = Keyframer {
+      nodes: vec![
+        node(10, None),
+        node(11, Some(10)),
+        node(12, Some(11)),
+        node(13, Some(42)),
+        node(14, Some(15)),
+        node(15, Some(14)),
+      ],
+      ..Keyframer::default()
+    };
+    keyframer.link();
+
+    assert_eq!(keyframer.nodes[0].children, [1]);
+    assert_eq!(keyframer.nodes[1].children, [2]);
+    assert_eq!(keyframer.nodes[2].parent, Some(1));
+    // Missing parents and cycles are broken up into roots.
+    assert_eq!(keyframer.nodes[3].parent, None);
+    assert_eq!(keyframer.nodes[4].parent, Some(5));
+    assert_eq!(keyframer.nodes[5].parent, None);
+    assert_eq!(keyframer.roots().collect::<Vec<_>>(), [0, 3, 5]);
+  }
+}
@@ -1,22 +1,71 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
+pub mod animation;
+pub mod camera;
 pub mod chunks;
+pub mod edit;
+mod error;
+pub mod keyframer;
+pub mod light;
+mod math;
+pub mod mesh;
+pub mod scene;
+#[cfg(test)]
+mod test_util;
+pub mod tree;
+mod writer;
 
 use std::fmt::Debug;
-use std::io::{BufRead, Cursor, Seek, SeekFrom};
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek};
+use std::path::Path;
 
 use byteorder::{LittleEndian, ReadBytesExt};
 use tracing::{debug, info};
 
+use crate::camera::Camera;
 use crate::chunks::{
-  EDIT_MATERIAL, EDIT_VERSION, MAIN3DS, MAIN_EDITOR, MAIN_KEYFRAMES, MAIN_VERSION, MATERIAL_NAME, MATERIAL_TEXTURE_MAP,
-  MATERIAL_TEXTURE_MAP_NAME,
+  CAM_RANGES, CAM_SEE_CONE, CHUNK_INFO_SIZE, COL_RGB, COL_RGB_GAMMA, COL_TRU, COL_TRU_GAMMA, EDIT_AMBIENT, EDIT_BACKGR,
+  EDIT_CONFIG1, EDIT_MATERIAL, EDIT_OBJECT, EDIT_VERSION, KEYF_AMBIENT, KEYF_CAMERA, KEYF_CAMERA_TARGET, KEYF_CURTIME,
+  KEYF_FRAMES, KEYF_HDR, KEYF_LIGHT, KEYF_LIGHT_TARGET, KEYF_OBJDES, KEYF_SPOTLIGHT, LIT_ATTENUATE, LIT_INNER_RANGE,
+  LIT_LOCAL_SHADOW, LIT_MULTIPLIER, LIT_OFF, LIT_OUTER_RANGE, LIT_SEE_CONE, LIT_SHADOWED, LIT_SPOT, LIT_SPOT_ASPECT,
+  LIT_SPOT_OVERSHOOT, LIT_SPOT_RECTANGULAR, LIT_SPOT_ROLL, MAIN3DS, MAIN_EDITOR, MAIN_KEYFRAMES, MAIN_VERSION,
+  MATERIAL_ADDITIVE, MATERIAL_AMBIENT, MATERIAL_BUMP_MAP, MATERIAL_BUMP_MASK, MATERIAL_DECAL, MATERIAL_DIFFUSE,
+  MATERIAL_FACE_MAP, MATERIAL_NAME, MATERIAL_OPACITY_MAP, MATERIAL_OPACITY_MASK, MATERIAL_REFLECTION_BLUR,
+  MATERIAL_REFLECTION_MAP, MATERIAL_REFLECTION_MASK, MATERIAL_SELF_ILLUMINATION, MATERIAL_SELF_ILLUMINATION_MAP,
+  MATERIAL_SELF_ILLUMINATION_MASK, MATERIAL_SHADING, MATERIAL_SHININESS, MATERIAL_SHININESS_MAP,
+  MATERIAL_SHININESS_MASK, MATERIAL_SHININESS_STRENGTH, MATERIAL_SPECULAR, MATERIAL_SPECULAR_MAP,
+  MATERIAL_SPECULAR_MASK, MATERIAL_TEXTURE_MAP, MATERIAL_TEXTURE_MAP2, MATERIAL_TEXTURE_MAP_ANGLE,
+  MATERIAL_TEXTURE_MAP_BLUR, MATERIAL_TEXTURE_MAP_NAME, MATERIAL_TEXTURE_MAP_TILING, MATERIAL_TEXTURE_MAP_TINT1,
+  MATERIAL_TEXTURE_MAP_TINT2, MATERIAL_TEXTURE_MAP_TINT_B, MATERIAL_TEXTURE_MAP_TINT_G, MATERIAL_TEXTURE_MAP_TINT_R,
+  MATERIAL_TEXTURE_MAP_U_OFFSET, MATERIAL_TEXTURE_MAP_U_SCALE, MATERIAL_TEXTURE_MAP_V_OFFSET,
+  MATERIAL_TEXTURE_MAP_V_SCALE, MATERIAL_TEXTURE_MASK, MATERIAL_TEXTURE_MASK2, MATERIAL_TRANSPARENCY,
+  MATERIAL_TRANSPARENCY_FALLOFF, MATERIAL_TWO_SIDED, MATERIAL_WIRE, MATERIAL_WIRE_THICKNESS, NODE_BOUNDBOX,
+  NODE_COL_TRACK, NODE_FALL_TRACK, NODE_FOV_TRACK, NODE_HDR, NODE_HIDE_TRACK, NODE_HOT_TRACK, NODE_ID,
+  NODE_INSTANCE_NAME, NODE_MORPH_TRACK, NODE_PIVOT, NODE_POS_TRACK, NODE_ROLL_TRACK, NODE_ROT_TRACK, NODE_SCL_TRACK,
+  OBJ_CAMERA, OBJ_LIGHT, OBJ_TRIMESH, PCT_FLOAT, PCT_INT, TRI_FACEL1, TRI_LOCAL, TRI_MAPPINGCOORS, TRI_MATERIAL,
+  TRI_SMOOTH, TRI_VERTEXL,
 };
+pub use crate::error::{Error, ErrorKind, Result};
+use crate::keyframer::{Key, Keyframer, Node, NodeKind, Rotation, Track};
+use crate::light::{Light, Shadow, Spotlight};
+use crate::mesh::{MaterialGroup, TriMesh};
+use crate::tree::{RawChunk, Siblings, UnknownChunk};
+pub use crate::writer::Writer3DS;
+
+/// Deepest chunk nesting the parser accepts. Real files nest a handful of levels, so this only stops crafted input
+/// from exhausting the stack.
+const MAX_CHUNK_DEPTH: usize = 64;
 
-pub struct Parser3DS<'a> {
-  data: &'a mut Cursor<&'a [u8]>,
+/// Parser for 3DS files, reading from any seekable input.
+pub struct Parser3DS<R> {
+  data: R,
+  /// Offset of the reader relative to where it was when the parser was created.
+  position: u64,
+  path: Vec<ChunkInfo>,
 }
 
+#[derive(Clone, Copy)]
 pub struct ChunkInfo {
   id: u16,
   offset: u64,
@@ -24,6 +73,16 @@ pub struct ChunkInfo {
 }
 
 impl ChunkInfo {
+  #[must_use]
+  pub const fn get_id(&self) -> u16 {
+    self.id
+  }
+
+  #[must_use]
+  pub const fn get_offset(&self) -> u64 {
+    self.offset
+  }
+
   #[must_use]
   pub const fn get_end(&self) -> u64 {
     self.offset + self.next_chunk_offset as u64
@@ -39,190 +98,1910 @@ impl Debug for ChunkInfo {
   }
 }
 
+#[deprecated(note = "the parser no longer needs a `Cursor`; compare `Cursor::position` with the length of the data")]
 pub trait CursorExt {
   fn remaining(&self) -> u64;
 }
 
+#[allow(deprecated)]
 impl<T: AsRef<[u8]>> CursorExt for Cursor<T> {
   fn remaining(&self) -> u64 {
     self.get_ref().as_ref().len() as u64 - self.position()
   }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Main {
+  /// Version of the file format, stored in a [`MAIN_VERSION`] chunk.
+  Version(u32),
   Editor(Vec<Editor>),
+  Keyframer(Keyframer),
+  /// Chunk the parser does not understand, kept as it was read.
+  Unknown(RawChunk),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum Editor {
+  /// Version of the mesh data, stored in an [`EDIT_VERSION`] chunk.
+  Version(u32),
+  /// Size of one unit in inches, stored in an [`EDIT_CONFIG1`] chunk.
+  MasterScale(f32),
+  /// Ambient light color, stored in an [`EDIT_AMBIENT`] chunk.
+  Ambient(Color),
+  /// Solid background color, stored in an [`EDIT_BACKGR`] chunk.
+  Background(Color),
   Material(Vec<Material>),
+  Object {
+    name: String,
+    kind: ObjectKind,
+    /// Sub-chunks of the object next to its mesh, light or camera that the parser does not understand.
+    unknown: Vec<UnknownChunk>,
+  },
+  /// Chunk the parser does not understand, including objects that are neither meshes, lights nor cameras.
+  Unknown(RawChunk),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectKind {
+  TriMesh(TriMesh),
+  Light(Light),
+  Camera(Camera),
+}
+
+/// Property of a material. Percentages are stored as fractions in the `0.0..=1.0` range.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Material {
   Name(String),
+  Ambient(Color),
+  Diffuse(Color),
+  Specular(Color),
+  Shininess(f32),
+  ShininessStrength(f32),
+  Transparency(f32),
+  TransparencyFalloff(f32),
+  ReflectionBlur(f32),
+  SelfIllumination(f32),
+  TwoSided,
+  Decal,
+  /// Transparency falloff is additive instead of subtractive.
+  AdditiveTransparency,
+  Wireframe,
+  WireThickness(f32),
+  FaceMap,
+  Shading(Shading),
   TextureMap(Vec<MaterialTextureMap>),
+  TextureMap2(Vec<MaterialTextureMap>),
+  OpacityMap(Vec<MaterialTextureMap>),
+  BumpMap(Vec<MaterialTextureMap>),
+  SpecularMap(Vec<MaterialTextureMap>),
+  ShininessMap(Vec<MaterialTextureMap>),
+  SelfIlluminationMap(Vec<MaterialTextureMap>),
+  ReflectionMap(Vec<MaterialTextureMap>),
+  TextureMask(Vec<MaterialTextureMap>),
+  TextureMask2(Vec<MaterialTextureMap>),
+  OpacityMask(Vec<MaterialTextureMap>),
+  BumpMask(Vec<MaterialTextureMap>),
+  ShininessMask(Vec<MaterialTextureMap>),
+  SpecularMask(Vec<MaterialTextureMap>),
+  SelfIlluminationMask(Vec<MaterialTextureMap>),
+  ReflectionMask(Vec<MaterialTextureMap>),
+  Unknown(RawChunk),
+}
+
+/// Shading type of a material, stored in a [`MATERIAL_SHADING`] chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shading {
+  Wireframe,
+  Flat,
+  Gouraud,
+  Phong,
+  Metal,
+  Unknown(u16),
+}
+
+impl From<u16> for Shading {
+  fn from(value: u16) -> Self {
+    match value {
+      0 => Self::Wireframe,
+      1 => Self::Flat,
+      2 => Self::Gouraud,
+      3 => Self::Phong,
+      4 => Self::Metal,
+      value => Self::Unknown(value),
+    }
+  }
+}
+
+impl From<Shading> for u16 {
+  fn from(shading: Shading) -> Self {
+    match shading {
+      Shading::Wireframe => 0,
+      Shading::Flat => 1,
+      Shading::Gouraud => 2,
+      Shading::Phong => 3,
+      Shading::Metal => 4,
+      Shading::Unknown(value) => value,
+    }
+  }
+}
+
+/// Color read from a chunk containing color sub-chunks, with components in the `0.0..=1.0` range.
+///
+/// Files usually store each color once, but some exporters add a gamma-corrected copy next to the plain one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Color {
+  /// Value of a [`COL_RGB`] or [`COL_TRU`] chunk.
+  pub linear: Option<[f32; 3]>,
+  /// Value of a [`COL_RGB_GAMMA`] or [`COL_TRU_GAMMA`] chunk.
+  pub gamma_corrected: Option<[f32; 3]>,
+  /// Sub-chunks of the color the parser does not understand, in file order.
+  pub unknown: Vec<UnknownChunk>,
 }
 
-#[derive(Debug)]
+/// Finds the material with the given name among the parsed editor items.
+#[must_use]
+pub fn find_material<'a>(items: &'a [Editor], name: &str) -> Option<&'a [Material]> {
+  items.iter().find_map(|item| match item {
+    Editor::Material(material)
+      if material
+        .iter()
+        .any(|property| matches!(property, Material::Name(material_name) if material_name == name)) =>
+    {
+      Some(material.as_slice())
+    }
+    _ => None,
+  })
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum MaterialTextureMap {
   Name(String),
+  /// Strength of the map as a fraction in the `0.0..=1.0` range.
+  Amount(f32),
+  Tiling(Tiling),
+  Blur(f32),
+  UScale(f32),
+  VScale(f32),
+  UOffset(f32),
+  VOffset(f32),
+  /// Rotation of the map in degrees.
+  Angle(f32),
+  Tint1([f32; 3]),
+  Tint2([f32; 3]),
+  RedTint([f32; 3]),
+  GreenTint([f32; 3]),
+  BlueTint([f32; 3]),
+  Unknown(RawChunk),
 }
 
-impl<'a> Parser3DS<'a> {
-  pub fn new(data: &'a mut Cursor<&'a [u8]>) -> Self {
-    Parser3DS { data }
+/// Tiling and filtering flags of a texture map, stored in a [`MATERIAL_TEXTURE_MAP_TILING`] chunk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tiling(pub u16);
+
+impl Tiling {
+  pub const DECAL: u16 = 0x0001;
+  pub const MIRROR: u16 = 0x0002;
+  pub const NEGATIVE: u16 = 0x0008;
+  pub const NO_TILE: u16 = 0x0010;
+  pub const SUMMED_AREA: u16 = 0x0020;
+  pub const ALPHA_SOURCE: u16 = 0x0040;
+  pub const TINT: u16 = 0x0080;
+  pub const IGNORE_ALPHA: u16 = 0x0100;
+  pub const RGB_TINT: u16 = 0x0200;
+
+  #[must_use]
+  pub const fn contains(self, flag: u16) -> bool {
+    self.0 & flag == flag
   }
+}
 
-  pub fn read_chunk_info(&mut self) -> ChunkInfo {
-    let offset = self.data.position();
-    let id = self.data.read_u16::<LittleEndian>().unwrap();
-    let next_chunk_offset = self.data.read_u32::<LittleEndian>().unwrap();
-    ChunkInfo {
+/// Builds the matrix that transforms texture coordinates according to the parameters of a texture map.
+///
+/// Coordinates are scaled, then rotated counter-clockwise by the map angle and finally moved by the offset, all
+/// around the origin. The matrix is row-major and transforms column vectors `[u, v, 1]`.
+#[must_use]
+pub fn uv_transform(map: &[MaterialTextureMap]) -> [[f32; 3]; 3] {
+  let (mut scale, mut offset, mut angle) = ([1.0, 1.0], [0.0, 0.0], 0.0_f32);
+  for parameter in map {
+    match *parameter {
+      MaterialTextureMap::UScale(value) => scale[0] = value,
+      MaterialTextureMap::VScale(value) => scale[1] = value,
+      MaterialTextureMap::UOffset(value) => offset[0] = value,
+      MaterialTextureMap::VOffset(value) => offset[1] = value,
+      MaterialTextureMap::Angle(value) => angle = value.to_radians(),
+      _ => {}
+    }
+  }
+
+  let (sin, cos) = angle.sin_cos();
+  [
+    [cos * scale[0], -sin * scale[1], offset[0]],
+    [sin * scale[0], cos * scale[1], offset[1]],
+    [0.0, 0.0, 1.0],
+  ]
+}
+
+impl<'a> Parser3DS<Cursor<&'a [u8]>> {
+  /// Creates a parser for a file that is already in memory.
+  #[must_use]
+  pub const fn from_bytes(bytes: &'a [u8]) -> Self {
+    Self::new(Cursor::new(bytes))
+  }
+}
+
+impl Parser3DS<BufReader<File>> {
+  /// Opens a file for parsing.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the file cannot be opened.
+  pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+    let file = File::open(path).map_err(|error| Error::new(error.into(), 0, Vec::new()))?;
+    Ok(Self::new(BufReader::new(file)))
+  }
+}
+
+impl<R: Read + Seek> Parser3DS<R> {
+  /// Creates a parser for a file that starts at the current position of the reader.
+  ///
+  /// Offsets in errors and chunk trees are counted from that position.
+  pub const fn new(data: R) -> Self {
+    Self {
+      data,
+      position: 0,
+      path: Vec::new(),
+    }
+  }
+
+  /// Returns the underlying reader.
+  pub fn into_inner(self) -> R {
+    self.data
+  }
+
+  /// Creates an error located at the given offset inside the chunk that is currently being read.
+  fn error_at(&self, offset: u64, kind: impl Into<ErrorKind>) -> Error {
+    Error::new(kind.into(), offset, self.path.clone())
+  }
+
+  fn error(&self, kind: impl Into<ErrorKind>) -> Error {
+    self.error_at(self.position, kind)
+  }
+
+  /// Moves the reader to the given offset.
+  fn seek(&mut self, offset: u64) -> Result<()> {
+    let delta = i128::from(offset) - i128::from(self.position);
+    let result = i64::try_from(delta)
+      .map_err(io::Error::other)
+      .and_then(|delta| self.data.seek_relative(delta));
+    result.map_err(|error| self.error(error))?;
+    self.position = offset;
+    Ok(())
+  }
+
+  /// Returns the number of bytes between the current position and the end of the input, leaving the position as it is.
+  fn remaining(&mut self) -> Result<u64> {
+    let result = self.data.stream_position();
+    let current = result.map_err(|error| self.error(error))?;
+    let result = self.data.seek(io::SeekFrom::End(0));
+    let end = result.map_err(|error| self.error(error))?;
+    let result = self.data.seek(io::SeekFrom::Start(current));
+    result.map_err(|error| self.error(error))?;
+    Ok(end.saturating_sub(current))
+  }
+
+  /// Reads the header of the chunk at the current position and enters it.
+  ///
+  /// Every chunk entered this way must be left with [`Parser3DS::seek_to_next_chunk`], so that errors report the
+  /// correct chunk path.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the header is truncated, declares a length that does not fit into its parent chunk or is
+  /// nested too deeply.
+  pub fn read_chunk_info(&mut self) -> Result<ChunkInfo> {
+    let offset = self.position;
+    let id = self.read_u16()?;
+    let next_chunk_offset = self.read_u32()?;
+    let info = ChunkInfo {
       id,
       offset,
       next_chunk_offset,
+    };
+    self.path.push(info);
+
+    let parent_end = match self.path.iter().rev().nth(1) {
+      Some(parent) => parent.get_end(),
+      None => self.position + self.remaining()?,
+    };
+    if u64::from(next_chunk_offset) < CHUNK_INFO_SIZE || info.get_end() > parent_end {
+      return Err(self.error_at(offset, ErrorKind::BadChunkLength(next_chunk_offset)));
+    }
+    if self.path.len() > MAX_CHUNK_DEPTH {
+      return Err(self.error_at(offset, ErrorKind::NestingTooDeep));
+    }
+
+    Ok(info)
+  }
+
+  /// Leaves the given chunk and moves the cursor past its end.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying reader fails to seek.
+  pub fn seek_to_next_chunk(&mut self, info: &ChunkInfo) -> Result<()> {
+    if self.path.last().is_some_and(|last| last.offset == info.offset) {
+      self.path.pop();
+    }
+
+    self.seek(info.get_end())
+  }
+
+  fn read_u8(&mut self) -> Result<u8> {
+    let offset = self.position;
+    let result = self.data.read_u8();
+    let value = result.map_err(|error| self.error_at(offset, error))?;
+    self.position += 1;
+    Ok(value)
+  }
+
+  fn read_u16(&mut self) -> Result<u16> {
+    let offset = self.position;
+    let result = self.data.read_u16::<LittleEndian>();
+    let value = result.map_err(|error| self.error_at(offset, error))?;
+    self.position += 2;
+    Ok(value)
+  }
+
+  fn read_i16(&mut self) -> Result<i16> {
+    let offset = self.position;
+    let result = self.data.read_i16::<LittleEndian>();
+    let value = result.map_err(|error| self.error_at(offset, error))?;
+    self.position += 2;
+    Ok(value)
+  }
+
+  fn read_u32(&mut self) -> Result<u32> {
+    let offset = self.position;
+    let result = self.data.read_u32::<LittleEndian>();
+    let value = result.map_err(|error| self.error_at(offset, error))?;
+    self.position += 4;
+    Ok(value)
+  }
+
+  fn read_bytes(&mut self, length: u64) -> Result<Vec<u8>> {
+    let offset = self.position;
+    let mut bytes = vec![0; usize::try_from(length).map_err(|_| self.error(ErrorKind::UnexpectedEof))?];
+    let result = self.data.read_exact(&mut bytes);
+    result.map_err(|error| self.error_at(offset, error))?;
+    self.position += length;
+    Ok(bytes)
+  }
+
+  fn read_f32(&mut self) -> Result<f32> {
+    let offset = self.position;
+    let result = self.data.read_f32::<LittleEndian>();
+    let value = result.map_err(|error| self.error_at(offset, error))?;
+    self.position += 4;
+    Ok(value)
+  }
+
+  fn read_vector(&mut self) -> Result<[f32; 3]> {
+    Ok([self.read_f32()?, self.read_f32()?, self.read_f32()?])
+  }
+
+  /// Reads the element count of an array and checks that the array fits into the given chunk.
+  fn read_count(&mut self, info: &ChunkInfo, element_size: u64) -> Result<usize> {
+    let count = self.read_u16()?;
+    if self.position + u64::from(count) * element_size > info.get_end() {
+      return Err(self.error(ErrorKind::UnexpectedEof));
     }
+
+    Ok(usize::from(count))
   }
 
-  pub fn seek_to_next_chunk(&mut self, info: &ChunkInfo) {
-    self
-      .data
-      .seek(SeekFrom::Start(info.offset + info.next_chunk_offset as u64))
-      .unwrap();
+  /// Reads a null-terminated string that must end before the end of the given chunk.
+  fn read_string(&mut self, info: &ChunkInfo) -> Result<String> {
+    let offset = self.position;
+    let mut bytes = Vec::new();
+    loop {
+      if self.position >= info.get_end() {
+        return Err(self.error(ErrorKind::UnexpectedEof));
+      }
+      match self.read_u8()? {
+        b'\0' => break,
+        byte => bytes.push(byte),
+      }
+    }
+
+    String::from_utf8(bytes).map_err(|error| self.error_at(offset, error))
   }
 
-  pub fn read_main(&mut self) -> Vec<Main> {
+  /// Reads the whole file, starting from the root [`MAIN3DS`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the file is truncated, malformed or does not start with a [`MAIN3DS`] chunk.
+  pub fn read_main(&mut self) -> Result<Vec<Main>> {
     let mut items = Vec::new();
-    let info = self.read_chunk_info();
+    let info = self.read_chunk_info()?;
     debug!("root chunk info: {:?}", info);
     match info.id {
       MAIN3DS => {
-        while self.data.position() < info.get_end() {
-          let info = self.read_chunk_info();
+        while self.position < info.get_end() {
+          let info = self.read_chunk_info()?;
           debug!("main chunk {:?}", info);
           match info.id {
             MAIN_VERSION => {
-              debug!("version: 0x{:x}", info.id);
-              self.seek_to_next_chunk(&info);
+              let version = self.read_u32()?;
+              debug!("version: {}", version);
+              items.push(Main::Version(version));
+              self.seek_to_next_chunk(&info)?;
             }
             MAIN_EDITOR => {
               debug!("scene chunk");
-              items.push(Main::Editor(self.read_editor(&info)));
-              self.seek_to_next_chunk(&info);
+              items.push(Main::Editor(self.read_editor(&info)?));
+              self.seek_to_next_chunk(&info)?;
             }
             MAIN_KEYFRAMES => {
               debug!("animation chunk");
-              self.seek_to_next_chunk(&info);
+              items.push(Main::Keyframer(self.read_keyframer(&info)?));
+              self.seek_to_next_chunk(&info)?;
+            }
+            _ => {
+              debug!("unknown main chunk {:?}", info);
+              items.push(Main::Unknown(self.read_raw_chunk(&info)?));
+              self.seek_to_next_chunk(&info)?;
             }
-            _ => unimplemented!(),
           }
         }
       }
-      _ => unimplemented!(),
+      id => return Err(self.error_at(info.offset, ErrorKind::UnknownRootChunk(id))),
     }
+    self.seek_to_next_chunk(&info)?;
 
-    items
+    Ok(items)
   }
 
-  pub fn read_editor(&mut self, info: &ChunkInfo) -> Vec<Editor> {
+  /// Reads the children of a [`MAIN_EDITOR`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_editor(&mut self, info: &ChunkInfo) -> Result<Vec<Editor>> {
     let mut items = Vec::new();
-    while self.data.position() < info.get_end() {
-      let info = self.read_chunk_info();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
       // debug!("editor chunk info: {:?}", info);
 
       match info.id {
         EDIT_VERSION => {
-          debug!("editor version: {:?}", info);
-          self.seek_to_next_chunk(&info);
+          let version = self.read_u32()?;
+          debug!("editor version: {}", version);
+          items.push(Editor::Version(version));
+          self.seek_to_next_chunk(&info)?;
+        }
+        EDIT_CONFIG1 => {
+          let scale = self.read_f32()?;
+          info!("editor master scale: {}", scale);
+          items.push(Editor::MasterScale(scale));
+          self.seek_to_next_chunk(&info)?;
+        }
+        EDIT_AMBIENT => {
+          let color = self.read_color(&info)?;
+          info!("editor ambient color: {:?}", color);
+          items.push(Editor::Ambient(color));
+          self.seek_to_next_chunk(&info)?;
+        }
+        EDIT_BACKGR => {
+          let color = self.read_color(&info)?;
+          info!("editor background color: {:?}", color);
+          items.push(Editor::Background(color));
+          self.seek_to_next_chunk(&info)?;
         }
         EDIT_MATERIAL => {
           debug!("editor material {:?}", info);
-          items.push(Editor::Material(self.read_material(&info)));
-          self.seek_to_next_chunk(&info);
+          items.push(Editor::Material(self.read_material(&info)?));
+          self.seek_to_next_chunk(&info)?;
+        }
+        EDIT_OBJECT => {
+          debug!("editor object {:?}", info);
+          let name = self.read_string(&info)?;
+          info!("object name: {:?}", name);
+          let mut unknown = Vec::new();
+          if let Some(kind) = self.read_object(&info, &mut unknown)? {
+            items.push(Editor::Object { name, kind, unknown });
+          } else {
+            debug!("object {:?} has no known kind", name);
+            self.seek(info.offset + CHUNK_INFO_SIZE)?;
+            items.push(Editor::Unknown(self.read_raw_chunk(&info)?));
+          }
+          self.seek_to_next_chunk(&info)?;
         }
         _ => {
           debug!("unknown editor chunk {:?}", info);
-          self.seek_to_next_chunk(&info);
+          items.push(Editor::Unknown(self.read_raw_chunk(&info)?));
+          self.seek_to_next_chunk(&info)?;
         }
-      };
+      }
+    }
+
+    Ok(items)
+  }
+
+  /// Reads the children of an [`EDIT_OBJECT`] chunk, positioned right after the object name. Children the parser
+  /// does not understand are added to `unknown`.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_object(&mut self, info: &ChunkInfo, unknown: &mut Vec<UnknownChunk>) -> Result<Option<ObjectKind>> {
+    let mut kind = None;
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("object chunk info: {:?}", info);
+
+      match info.id {
+        OBJ_TRIMESH => {
+          debug!("object triangle mesh {:?}", info);
+          kind = Some(ObjectKind::TriMesh(self.read_trimesh(&info)?));
+          self.seek_to_next_chunk(&info)?;
+        }
+        OBJ_LIGHT => {
+          debug!("object light {:?}", info);
+          kind = Some(ObjectKind::Light(self.read_light(&info)?));
+          self.seek_to_next_chunk(&info)?;
+        }
+        OBJ_CAMERA => {
+          debug!("object camera {:?}", info);
+          kind = Some(ObjectKind::Camera(self.read_camera(&info)?));
+          self.seek_to_next_chunk(&info)?;
+        }
+        _ => {
+          debug!("unknown object chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, unknown)?;
+          self.seek_to_next_chunk(&info)?;
+        }
+      }
+      siblings.push(info.id);
+    }
+
+    Ok(kind)
+  }
+
+  /// Reads the children of an [`OBJ_TRIMESH`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_trimesh(&mut self, info: &ChunkInfo) -> Result<TriMesh> {
+    let mut mesh = TriMesh::default();
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("triangle mesh chunk info: {:?}", info);
+
+      match info.id {
+        TRI_VERTEXL => {
+          let count = self.read_count(&info, 3 * 4)?;
+          mesh.vertices.reserve(count);
+          for _ in 0..count {
+            mesh.vertices.push(self.read_vector()?);
+          }
+          info!("triangle mesh vertices: {}", count);
+          self.check_mapping_count(&mesh)?;
+
+          self.seek_to_next_chunk(&info)?;
+        }
+        TRI_MAPPINGCOORS => {
+          let count = self.read_count(&info, 2 * 4)?;
+          mesh.uvs.reserve(count);
+          for _ in 0..count {
+            mesh.uvs.push([self.read_f32()?, self.read_f32()?]);
+          }
+          info!("triangle mesh texture coordinates: {}", count);
+          self.check_mapping_count(&mesh)?;
+
+          self.seek_to_next_chunk(&info)?;
+        }
+        TRI_FACEL1 => {
+          let count = self.read_count(&info, 4 * 2)?;
+          mesh.faces.reserve(count);
+          mesh.face_flags.reserve(count);
+          for _ in 0..count {
+            mesh.faces.push([self.read_u16()?, self.read_u16()?, self.read_u16()?]);
+            mesh.face_flags.push(self.read_u16()?);
+          }
+          info!("triangle mesh faces: {}", count);
+          if !mesh.vertices.is_empty() {
+            self.check_face_indices(&mesh)?;
+          }
+          self.read_face_list(&info, &mut mesh)?;
+
+          self.seek_to_next_chunk(&info)?;
+        }
+        TRI_LOCAL => {
+          let mut local = [[0.0; 3]; 4];
+          for row in &mut local {
+            *row = self.read_vector()?;
+          }
+          info!("triangle mesh local coordinate system: {:?}", local);
+          mesh.local = Some(local);
+
+          self.seek_to_next_chunk(&info)?;
+        }
+        _ => {
+          debug!("unknown triangle mesh chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut mesh.unknown)?;
+          self.seek_to_next_chunk(&info)?;
+        }
+      }
+      siblings.push(info.id);
+    }
+    // Faces stored before the vertices can only be checked once the whole mesh is read.
+    self.check_face_indices(&mesh)?;
+
+    Ok(mesh)
+  }
+
+  /// Checks that the texture coordinates read so far match the vertices of the mesh.
+  fn check_mapping_count(&self, mesh: &TriMesh) -> Result<()> {
+    if !mesh.vertices.is_empty() && !mesh.uvs.is_empty() && mesh.vertices.len() != mesh.uvs.len() {
+      return Err(self.error(ErrorKind::MappingCountMismatch {
+        vertices: mesh.vertices.len(),
+        uvs: mesh.uvs.len(),
+      }));
+    }
+
+    Ok(())
+  }
+
+  /// Checks that the faces read so far only refer to existing vertices.
+  fn check_face_indices(&self, mesh: &TriMesh) -> Result<()> {
+    let vertices = mesh.vertices.len();
+    match mesh
+      .faces
+      .iter()
+      .flatten()
+      .find(|&&index| usize::from(index) >= vertices)
+    {
+      Some(&index) => Err(self.error(ErrorKind::FaceIndexOutOfRange { index, vertices })),
+      None => Ok(()),
+    }
+  }
+
+  /// Reads the children of a [`TRI_FACEL1`] chunk, positioned right after the face array.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_face_list(&mut self, info: &ChunkInfo, mesh: &mut TriMesh) -> Result<()> {
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("face list chunk info: {:?}", info);
+
+      match info.id {
+        TRI_MATERIAL => {
+          let material_name = self.read_string(&info)?;
+          let count = self.read_count(&info, 2)?;
+          let mut faces = Vec::with_capacity(count);
+          for _ in 0..count {
+            faces.push(self.read_u16()?);
+          }
+          info!("face material {:?}: {} faces", material_name, count);
+          mesh.material_groups.push(MaterialGroup { material_name, faces });
+
+          self.seek_to_next_chunk(&info)?;
+        }
+        TRI_SMOOTH => {
+          let count = mesh.faces.len();
+          if self.position + count as u64 * 4 > info.get_end() {
+            return Err(self.error(ErrorKind::UnexpectedEof));
+          }
+          mesh.smoothing_groups.reserve(count);
+          for _ in 0..count {
+            mesh.smoothing_groups.push(self.read_u32()?);
+          }
+          info!("face smoothing groups: {}", count);
+
+          self.seek_to_next_chunk(&info)?;
+        }
+        _ => {
+          debug!("unknown face list chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut mesh.face_list_unknown)?;
+          self.seek_to_next_chunk(&info)?;
+        }
+      }
+      siblings.push(info.id);
+    }
+
+    Ok(())
+  }
+
+  /// Reads an [`OBJ_LIGHT`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the chunk or any of its children is truncated or malformed.
+  pub fn read_light(&mut self, info: &ChunkInfo) -> Result<Light> {
+    let mut light = Light {
+      position: self.read_vector()?,
+      ..Light::default()
+    };
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("light chunk info: {:?}", info);
+
+      match info.id {
+        COL_RGB | COL_TRU | COL_RGB_GAMMA | COL_TRU_GAMMA => self.read_color_chunk(&info, &mut light.color)?,
+        LIT_OFF => light.off = true,
+        LIT_ATTENUATE => light.attenuation = true,
+        LIT_INNER_RANGE => light.inner_range = Some(self.read_f32()?),
+        LIT_OUTER_RANGE => light.outer_range = Some(self.read_f32()?),
+        LIT_MULTIPLIER => light.multiplier = self.read_f32()?,
+        LIT_SPOT => light.spot = Some(self.read_spotlight(&info)?),
+        _ => {
+          debug!("unknown light chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut light.unknown)?;
+        }
+      }
+      self.seek_to_next_chunk(&info)?;
+      siblings.push(info.id);
+    }
+    info!("light: {:?}", light);
+
+    Ok(light)
+  }
+
+  /// Reads a [`LIT_SPOT`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the chunk or any of its children is truncated or malformed.
+  pub fn read_spotlight(&mut self, info: &ChunkInfo) -> Result<Spotlight> {
+    let mut spot = Spotlight {
+      target: self.read_vector()?,
+      hotspot: self.read_f32()?,
+      falloff: self.read_f32()?,
+      ..Spotlight::default()
+    };
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("spotlight chunk info: {:?}", info);
+
+      match info.id {
+        LIT_SPOT_ROLL => spot.roll = self.read_f32()?,
+        LIT_SHADOWED => spot.shadowed = true,
+        LIT_LOCAL_SHADOW => {
+          spot.shadow = Some(Shadow {
+            bias: self.read_f32()?,
+            filter: self.read_f32()?,
+            map_size: self.read_u16()?,
+          });
+        }
+        LIT_SEE_CONE => spot.cone_visible = true,
+        LIT_SPOT_RECTANGULAR => spot.rectangular = true,
+        LIT_SPOT_ASPECT => spot.aspect = Some(self.read_f32()?),
+        LIT_SPOT_OVERSHOOT => spot.overshoot = true,
+        _ => {
+          debug!("unknown spotlight chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut spot.unknown)?;
+        }
+      }
+      self.seek_to_next_chunk(&info)?;
+      siblings.push(info.id);
+    }
+
+    Ok(spot)
+  }
+
+  /// Reads an [`OBJ_CAMERA`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the chunk or any of its children is truncated or malformed.
+  pub fn read_camera(&mut self, info: &ChunkInfo) -> Result<Camera> {
+    let mut camera = Camera {
+      position: self.read_vector()?,
+      target: self.read_vector()?,
+      roll: self.read_f32()?,
+      lens: self.read_f32()?,
+      ..Camera::default()
+    };
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("camera chunk info: {:?}", info);
+
+      match info.id {
+        CAM_SEE_CONE => camera.cone_visible = true,
+        CAM_RANGES => {
+          camera.near_range = Some(self.read_f32()?);
+          camera.far_range = Some(self.read_f32()?);
+        }
+        _ => {
+          debug!("unknown camera chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut camera.unknown)?;
+        }
+      }
+      self.seek_to_next_chunk(&info)?;
+      siblings.push(info.id);
     }
+    info!("camera: {:?}", camera);
 
-    items
+    Ok(camera)
   }
 
-  pub fn read_material(&mut self, info: &ChunkInfo) -> Vec<Material> {
+  /// Reads the children of an [`EDIT_MATERIAL`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  #[allow(clippy::too_many_lines)]
+  pub fn read_material(&mut self, info: &ChunkInfo) -> Result<Vec<Material>> {
     let mut items = Vec::new();
-    while self.data.position() < info.get_end() {
-      let info = self.read_chunk_info();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
       // debug!("material chunk info: {:?}", info);
 
       match info.id {
         MATERIAL_NAME => {
-          let mut name = Vec::new();
-          self.data.read_until(b'\0', &mut name).unwrap();
-          name.pop();
-          let name = String::from_utf8(name).unwrap();
+          let name = self.read_string(&info)?;
           info!("material name: {:?}", name);
           items.push(Material::Name(name));
 
-          self.seek_to_next_chunk(&info);
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_AMBIENT => {
+          let color = self.read_color(&info)?;
+          info!("material ambient color: {:?}", color);
+          items.push(Material::Ambient(color));
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_DIFFUSE => {
+          let color = self.read_color(&info)?;
+          info!("material diffuse color: {:?}", color);
+          items.push(Material::Diffuse(color));
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_SPECULAR => {
+          let color = self.read_color(&info)?;
+          info!("material specular color: {:?}", color);
+          items.push(Material::Specular(color));
+          self.seek_to_next_chunk(&info)?;
         }
-        MATERIAL_TEXTURE_MAP => {
-          info!("material texture map: {:?}", info);
-          items.push(Material::TextureMap(self.read_material_texture_map(&info)));
-          self.seek_to_next_chunk(&info);
+        MATERIAL_SHININESS
+        | MATERIAL_SHININESS_STRENGTH
+        | MATERIAL_TRANSPARENCY
+        | MATERIAL_TRANSPARENCY_FALLOFF
+        | MATERIAL_REFLECTION_BLUR
+        | MATERIAL_SELF_ILLUMINATION => {
+          if let Some(value) = self.read_percentage(&info)? {
+            let property = match info.id {
+              MATERIAL_SHININESS => Material::Shininess(value),
+              MATERIAL_SHININESS_STRENGTH => Material::ShininessStrength(value),
+              MATERIAL_TRANSPARENCY => Material::Transparency(value),
+              MATERIAL_TRANSPARENCY_FALLOFF => Material::TransparencyFalloff(value),
+              MATERIAL_REFLECTION_BLUR => Material::ReflectionBlur(value),
+              _ => Material::SelfIllumination(value),
+            };
+            info!("material property: {:?}", property);
+            items.push(property);
+          } else {
+            debug!("material property without percentage {:?}", info);
+            self.seek(info.offset + CHUNK_INFO_SIZE)?;
+            items.push(Material::Unknown(self.read_raw_chunk(&info)?));
+          }
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_TWO_SIDED | MATERIAL_DECAL | MATERIAL_ADDITIVE | MATERIAL_WIRE | MATERIAL_FACE_MAP => {
+          let property = match info.id {
+            MATERIAL_TWO_SIDED => Material::TwoSided,
+            MATERIAL_DECAL => Material::Decal,
+            MATERIAL_ADDITIVE => Material::AdditiveTransparency,
+            MATERIAL_WIRE => Material::Wireframe,
+            _ => Material::FaceMap,
+          };
+          info!("material flag: {:?}", property);
+          items.push(property);
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_WIRE_THICKNESS => {
+          let thickness = self.read_f32()?;
+          info!("material wire thickness: {}", thickness);
+          items.push(Material::WireThickness(thickness));
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_SHADING => {
+          let shading = Shading::from(self.read_u16()?);
+          info!("material shading: {:?}", shading);
+          items.push(Material::Shading(shading));
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_TEXTURE_MAP
+        | MATERIAL_TEXTURE_MAP2
+        | MATERIAL_OPACITY_MAP
+        | MATERIAL_BUMP_MAP
+        | MATERIAL_SPECULAR_MAP
+        | MATERIAL_SHININESS_MAP
+        | MATERIAL_SELF_ILLUMINATION_MAP
+        | MATERIAL_REFLECTION_MAP
+        | MATERIAL_TEXTURE_MASK
+        | MATERIAL_TEXTURE_MASK2
+        | MATERIAL_OPACITY_MASK
+        | MATERIAL_BUMP_MASK
+        | MATERIAL_SHININESS_MASK
+        | MATERIAL_SPECULAR_MASK
+        | MATERIAL_SELF_ILLUMINATION_MASK
+        | MATERIAL_REFLECTION_MASK => {
+          info!("material map {:?}", info);
+          let map = self.read_material_texture_map(&info)?;
+          items.push(match info.id {
+            MATERIAL_TEXTURE_MAP => Material::TextureMap(map),
+            MATERIAL_TEXTURE_MAP2 => Material::TextureMap2(map),
+            MATERIAL_OPACITY_MAP => Material::OpacityMap(map),
+            MATERIAL_BUMP_MAP => Material::BumpMap(map),
+            MATERIAL_SPECULAR_MAP => Material::SpecularMap(map),
+            MATERIAL_SHININESS_MAP => Material::ShininessMap(map),
+            MATERIAL_SELF_ILLUMINATION_MAP => Material::SelfIlluminationMap(map),
+            MATERIAL_REFLECTION_MAP => Material::ReflectionMap(map),
+            MATERIAL_TEXTURE_MASK => Material::TextureMask(map),
+            MATERIAL_TEXTURE_MASK2 => Material::TextureMask2(map),
+            MATERIAL_OPACITY_MASK => Material::OpacityMask(map),
+            MATERIAL_BUMP_MASK => Material::BumpMask(map),
+            MATERIAL_SHININESS_MASK => Material::ShininessMask(map),
+            MATERIAL_SPECULAR_MASK => Material::SpecularMask(map),
+            MATERIAL_SELF_ILLUMINATION_MASK => Material::SelfIlluminationMask(map),
+            _ => Material::ReflectionMask(map),
+          });
+          self.seek_to_next_chunk(&info)?;
         }
         _ => {
           debug!("unknown material chunk {:?}", info);
-          self.seek_to_next_chunk(&info);
+          items.push(Material::Unknown(self.read_raw_chunk(&info)?));
+          self.seek_to_next_chunk(&info)?;
         }
       }
     }
 
-    items
+    Ok(items)
   }
 
-  pub fn read_material_texture_map(&mut self, info: &ChunkInfo) -> Vec<MaterialTextureMap> {
+  /// Reads the children of a [`MATERIAL_TEXTURE_MAP`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_material_texture_map(&mut self, info: &ChunkInfo) -> Result<Vec<MaterialTextureMap>> {
     let mut items = Vec::new();
-    while self.data.position() < info.get_end() {
-      let info = self.read_chunk_info();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
       // debug!("material texture map chunk info: {:?}", info);
 
       match info.id {
         MATERIAL_TEXTURE_MAP_NAME => {
-          let mut name = Vec::new();
-          self.data.read_until(b'\0', &mut name).unwrap();
-          name.pop();
-          let name = String::from_utf8(name).unwrap();
+          let name = self.read_string(&info)?;
           info!("material texture map name: {:?}", name);
           items.push(MaterialTextureMap::Name(name));
 
-          self.seek_to_next_chunk(&info);
+          self.seek_to_next_chunk(&info)?;
+        }
+        PCT_INT | PCT_FLOAT => {
+          let amount = self.read_percentage_value(&info)?;
+          info!("material texture map amount: {}", amount);
+          items.push(MaterialTextureMap::Amount(amount));
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_TEXTURE_MAP_TILING => {
+          let tiling = Tiling(self.read_u16()?);
+          info!("material texture map tiling: {:?}", tiling);
+          items.push(MaterialTextureMap::Tiling(tiling));
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_TEXTURE_MAP_BLUR
+        | MATERIAL_TEXTURE_MAP_U_SCALE
+        | MATERIAL_TEXTURE_MAP_V_SCALE
+        | MATERIAL_TEXTURE_MAP_U_OFFSET
+        | MATERIAL_TEXTURE_MAP_V_OFFSET
+        | MATERIAL_TEXTURE_MAP_ANGLE => {
+          let value = self.read_f32()?;
+          let parameter = match info.id {
+            MATERIAL_TEXTURE_MAP_BLUR => MaterialTextureMap::Blur(value),
+            MATERIAL_TEXTURE_MAP_U_SCALE => MaterialTextureMap::UScale(value),
+            MATERIAL_TEXTURE_MAP_V_SCALE => MaterialTextureMap::VScale(value),
+            MATERIAL_TEXTURE_MAP_U_OFFSET => MaterialTextureMap::UOffset(value),
+            MATERIAL_TEXTURE_MAP_V_OFFSET => MaterialTextureMap::VOffset(value),
+            _ => MaterialTextureMap::Angle(value),
+          };
+          info!("material texture map parameter: {:?}", parameter);
+          items.push(parameter);
+          self.seek_to_next_chunk(&info)?;
+        }
+        MATERIAL_TEXTURE_MAP_TINT1
+        | MATERIAL_TEXTURE_MAP_TINT2
+        | MATERIAL_TEXTURE_MAP_TINT_R
+        | MATERIAL_TEXTURE_MAP_TINT_G
+        | MATERIAL_TEXTURE_MAP_TINT_B => {
+          let color = self.read_color_tru()?;
+          let parameter = match info.id {
+            MATERIAL_TEXTURE_MAP_TINT1 => MaterialTextureMap::Tint1(color),
+            MATERIAL_TEXTURE_MAP_TINT2 => MaterialTextureMap::Tint2(color),
+            MATERIAL_TEXTURE_MAP_TINT_R => MaterialTextureMap::RedTint(color),
+            MATERIAL_TEXTURE_MAP_TINT_G => MaterialTextureMap::GreenTint(color),
+            _ => MaterialTextureMap::BlueTint(color),
+          };
+          info!("material texture map tint: {:?}", parameter);
+          items.push(parameter);
+          self.seek_to_next_chunk(&info)?;
         }
         _ => {
           debug!("unknown material texture map chunk {:?}", info);
-          self.seek_to_next_chunk(&info);
+          items.push(MaterialTextureMap::Unknown(self.read_raw_chunk(&info)?));
+          self.seek_to_next_chunk(&info)?;
+        }
+      }
+    }
+
+    Ok(items)
+  }
+
+  /// Reads the children of a [`MAIN_KEYFRAMES`] chunk and links the nodes into a tree.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_keyframer(&mut self, info: &ChunkInfo) -> Result<Keyframer> {
+    let mut keyframer = Keyframer::default();
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("keyframer chunk info: {:?}", info);
+
+      let kind = match info.id {
+        KEYF_HDR => {
+          keyframer.revision = self.read_u16()?;
+          keyframer.filename = self.read_string(&info)?;
+          keyframer.length = self.read_u32()?;
+          None
+        }
+        KEYF_FRAMES => {
+          keyframer.segment = Some(self.read_u32()?..=self.read_u32()?);
+          None
+        }
+        KEYF_CURTIME => {
+          keyframer.current_frame = self.read_u32()?;
+          None
+        }
+        KEYF_AMBIENT => Some(NodeKind::Ambient),
+        KEYF_OBJDES => Some(NodeKind::Object),
+        KEYF_CAMERA => Some(NodeKind::Camera),
+        KEYF_CAMERA_TARGET => Some(NodeKind::CameraTarget),
+        KEYF_LIGHT => Some(NodeKind::Light),
+        KEYF_LIGHT_TARGET => Some(NodeKind::LightTarget),
+        KEYF_SPOTLIGHT => Some(NodeKind::Spotlight),
+        _ => {
+          debug!("unknown keyframer chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut keyframer.unknown)?;
+          None
+        }
+      };
+      if let Some(kind) = kind {
+        let id = u16::try_from(keyframer.nodes.len()).unwrap_or(u16::MAX);
+        keyframer.nodes.push(self.read_node(&info, Node::new(kind, id))?);
+      }
+      self.seek_to_next_chunk(&info)?;
+      siblings.push(info.id);
+    }
+    keyframer.link();
+
+    Ok(keyframer)
+  }
+
+  /// Reads the children of a keyframer node chunk into the given node.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_node(&mut self, info: &ChunkInfo, mut node: Node) -> Result<Node> {
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("node chunk info: {:?}", info);
+
+      match info.id {
+        NODE_ID => node.id = self.read_u16()?,
+        NODE_HDR => {
+          node.name = self.read_string(&info)?;
+          node.flags = [self.read_u16()?, self.read_u16()?];
+          node.parent_id = Some(self.read_u16()?).filter(|&parent| parent != u16::MAX);
+        }
+        NODE_INSTANCE_NAME => node.instance_name = Some(self.read_string(&info)?),
+        NODE_PIVOT => node.pivot = self.read_vector()?,
+        NODE_BOUNDBOX => node.bounding_box = Some([self.read_vector()?, self.read_vector()?]),
+        NODE_POS_TRACK => node.position = Some(self.read_track(&info, |parser, _| parser.read_vector())?),
+        NODE_ROT_TRACK => {
+          node.rotation = Some(self.read_track(&info, |parser, _| {
+            Ok(Rotation {
+              angle: parser.read_f32()?,
+              axis: parser.read_vector()?,
+            })
+          })?);
+        }
+        NODE_SCL_TRACK => node.scale = Some(self.read_track(&info, |parser, _| parser.read_vector())?),
+        NODE_FOV_TRACK => node.fov = Some(self.read_track(&info, |parser, _| parser.read_f32())?),
+        NODE_ROLL_TRACK => node.roll = Some(self.read_track(&info, |parser, _| parser.read_f32())?),
+        NODE_COL_TRACK => node.color = Some(self.read_track(&info, |parser, _| parser.read_vector())?),
+        NODE_MORPH_TRACK => node.morph = Some(self.read_track(&info, Self::read_string)?),
+        NODE_HOT_TRACK => node.hotspot = Some(self.read_track(&info, |parser, _| parser.read_f32())?),
+        NODE_FALL_TRACK => node.falloff = Some(self.read_track(&info, |parser, _| parser.read_f32())?),
+        NODE_HIDE_TRACK => node.hide = Some(self.read_track(&info, |_, _| Ok(()))?),
+        _ => {
+          debug!("unknown node chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut node.unknown)?;
         }
       }
+      self.seek_to_next_chunk(&info)?;
+      siblings.push(info.id);
     }
+    info!("keyframer node {:?} ({:?})", node.name, node.kind);
 
-    items
+    Ok(node)
+  }
+
+  /// Reads an animation track, using the given function to read the value of each key.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the track is truncated or malformed.
+  pub fn read_track<T>(
+    &mut self,
+    info: &ChunkInfo,
+    mut read_value: impl FnMut(&mut Self, &ChunkInfo) -> Result<T>,
+  ) -> Result<Track<T>> {
+    let flags = self.read_u16()?;
+    let reserved = [self.read_u32()?, self.read_u32()?];
+    let count = self.read_u32()?;
+    // Every key takes at least its frame and spline flags.
+    if self.position + u64::from(count) * 6 > info.get_end() {
+      return Err(self.error(ErrorKind::UnexpectedEof));
+    }
+
+    let mut keys = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+      let frame = self.read_u32()?;
+      let spline_flags = self.read_u16()?;
+      let mut spline = [0.0; 5];
+      for (bit, parameter) in spline.iter_mut().enumerate() {
+        if spline_flags & (1 << bit) != 0 {
+          *parameter = self.read_f32()?;
+        }
+      }
+      let [tension, continuity, bias, ease_to, ease_from] = spline;
+      keys.push(Key {
+        frame,
+        tension,
+        continuity,
+        bias,
+        ease_to,
+        ease_from,
+        value: read_value(self, info)?,
+      });
+    }
+    debug!("track {:?}: {} keys", info, keys.len());
+
+    Ok(Track { flags, reserved, keys })
+  }
+
+  /// Reads the color sub-chunks of a chunk that stores a color.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_color(&mut self, info: &ChunkInfo) -> Result<Color> {
+    let mut color = Color::default();
+    let mut siblings = Siblings::default();
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("color chunk info: {:?}", info);
+
+      match info.id {
+        COL_RGB | COL_TRU | COL_RGB_GAMMA | COL_TRU_GAMMA => self.read_color_chunk(&info, &mut color)?,
+        _ => {
+          debug!("unknown color chunk {:?}", info);
+          self.read_unknown_chunk(&info, &siblings, &mut color.unknown)?;
+        }
+      }
+      self.seek_to_next_chunk(&info)?;
+      siblings.push(info.id);
+    }
+
+    Ok(color)
+  }
+
+  /// Reads a single [`COL_RGB`], [`COL_TRU`], [`COL_RGB_GAMMA`] or [`COL_TRU_GAMMA`] chunk into the matching field of
+  /// the given color.
+  fn read_color_chunk(&mut self, info: &ChunkInfo, color: &mut Color) -> Result<()> {
+    match info.id {
+      COL_RGB => color.linear = Some(self.read_vector()?),
+      COL_TRU => color.linear = Some(self.read_color_tru()?),
+      COL_RGB_GAMMA => color.gamma_corrected = Some(self.read_vector()?),
+      _ => color.gamma_corrected = Some(self.read_color_tru()?),
+    }
+
+    Ok(())
+  }
+
+  /// Reads a chunk the parser does not understand into the given list, together with the sibling stored before it.
+  fn read_unknown_chunk(
+    &mut self,
+    info: &ChunkInfo,
+    siblings: &Siblings,
+    unknown: &mut Vec<UnknownChunk>,
+  ) -> Result<()> {
+    let chunk = self.read_raw_chunk(info)?;
+    unknown.push(UnknownChunk {
+      after: siblings.last(),
+      chunk,
+    });
+
+    Ok(())
+  }
+
+  /// Reads the percentage sub-chunk of a chunk that stores a percentage, as a fraction in the `0.0..=1.0` range.
+  ///
+  /// Returns [`None`] unless the chunk holds exactly one percentage sub-chunk and nothing else, so that the caller can
+  /// keep the chunk as it is.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if any of the children is truncated or malformed.
+  pub fn read_percentage(&mut self, info: &ChunkInfo) -> Result<Option<f32>> {
+    let mut values = Vec::new();
+    let mut unknown = false;
+    while self.position < info.get_end() {
+      let info = self.read_chunk_info()?;
+      // debug!("percentage chunk info: {:?}", info);
+
+      match info.id {
+        PCT_INT | PCT_FLOAT => values.push(self.read_percentage_value(&info)?),
+        _ => {
+          debug!("unknown percentage chunk {:?}", info);
+          unknown = true;
+        }
+      }
+      self.seek_to_next_chunk(&info)?;
+    }
+
+    Ok(match values[..] {
+      [value] if !unknown => Some(value),
+      _ => None,
+    })
+  }
+
+  /// Reads the value of a [`PCT_INT`] or [`PCT_FLOAT`] chunk as a fraction. Both store the value in percent.
+  fn read_percentage_value(&mut self, info: &ChunkInfo) -> Result<f32> {
+    if info.id == PCT_INT {
+      Ok(f32::from(self.read_i16()?) / 100.0)
+    } else {
+      Ok(self.read_f32()? / 100.0)
+    }
+  }
+
+  fn read_color_tru(&mut self) -> Result<[f32; 3]> {
+    Ok([self.read_u8()?, self.read_u8()?, self.read_u8()?].map(|value| f32::from(value) / 255.0))
   }
 }
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
   use std::fs;
 
   use super::*;
+  use crate::chunks::{OBJ_UNKNWN01, OBJ_UNKNWN02, TRI_VISIBLE};
+  use crate::test_util::chunk;
 
   #[test_log::test]
   fn it_works() {
-    let data = fs::read("test/tower.3ds").unwrap();
+    let mut parser = Parser3DS::from_path("test/tower.3ds").unwrap();
+    debug!("{:#?}", parser.read_main().unwrap());
+  }
+
+  fn read_editor(path: &str) -> Vec<Editor> {
+    parse_editor(&fs::read(path).unwrap())
+  }
+
+  fn parse_editor(data: &[u8]) -> Vec<Editor> {
+    let mut parser = Parser3DS::from_bytes(data);
+    let main = parser.read_main().unwrap();
+    let mut editors = main.into_iter().filter_map(|item| match item {
+      Main::Editor(editor) => Some(editor),
+      _ => None,
+    });
+    match (editors.next(), editors.next()) {
+      (Some(editor), None) => editor,
+      _ => panic!("expected a single editor chunk"),
+    }
+  }
+
+  #[test_log::test]
+  fn material_properties() {
+    let editor = read_editor("test/tower.3ds");
+    let Some(Editor::Material(material)) = editor.iter().find(|item| matches!(item, Editor::Material(_))) else {
+      panic!("expected a material");
+    };
+    for property in material {
+      if let Material::Ambient(color) | Material::Diffuse(color) | Material::Specular(color) = property {
+        assert!(color
+          .linear
+          .is_some_and(|rgb| rgb.iter().all(|value| (0.0..=1.0).contains(value))));
+        assert_eq!(color.gamma_corrected, None);
+      }
+    }
+    let count = |predicate: fn(&Material) -> bool| material.iter().filter(|property| predicate(property)).count();
+    assert_eq!(count(|property| matches!(property, Material::Diffuse(_))), 1);
+    assert_eq!(count(|property| matches!(property, Material::Shininess(_))), 1);
+    assert_eq!(count(|property| matches!(property, Material::SelfIllumination(_))), 1);
+    assert_eq!(count(|property| matches!(property, Material::WireThickness(_))), 1);
+    assert_eq!(count(|property| matches!(property, Material::TextureMap(_))), 1);
+    assert_eq!(
+      count(|property| matches!(property, Material::OpacityMap(_) | Material::BumpMap(_))),
+      0
+    );
+    assert_eq!(
+      count(|property| matches!(property, Material::Shading(Shading::Phong))),
+      1
+    );
+  }
+
+  #[test_log::test]
+  fn unknown_color_and_percentage_chunks() {
+    let material = chunk(
+      EDIT_MATERIAL,
+      &[
+        chunk(MATERIAL_NAME, b"odd\0"),
+        chunk(
+          MATERIAL_AMBIENT,
+          &[chunk(COL_TRU, &[255, 0, 0]), chunk(0x0099, &[1, 2])].concat(),
+        ),
+        chunk(MATERIAL_SHININESS, &[]),
+        chunk(
+          MATERIAL_TRANSPARENCY,
+          &[chunk(PCT_INT, &50i16.to_le_bytes()), chunk(0x0032, &[3])].concat(),
+        ),
+      ]
+      .concat(),
+    );
+    let editor = parse_editor(&editor_file(std::slice::from_ref(&material)));
+    let [Editor::Material(properties)] = editor.as_slice() else {
+      panic!("unexpected editor items {editor:?}");
+    };
+    let Material::Ambient(color) = &properties[1] else {
+      panic!("expected an ambient color");
+    };
+    assert_eq!(color.linear, Some([1.0, 0.0, 0.0]));
+    assert_eq!(color.unknown.len(), 1);
+    assert_eq!(color.unknown[0].data, [1, 2]);
+    assert!(matches!(&properties[2], Material::Unknown(chunk) if chunk.id == MATERIAL_SHININESS));
+    assert!(matches!(&properties[3], Material::Unknown(chunk) if chunk.id == MATERIAL_TRANSPARENCY));
+
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_editor(&editor).unwrap();
+    assert_eq!(writer.into_inner().into_inner(), material);
+  }
+
+  #[test_log::test]
+  fn texture_map_parameters() {
+    let editor = read_editor("test/tower.3ds");
+    let Some(Editor::Material(material)) = editor.iter().find(|item| matches!(item, Editor::Material(_))) else {
+      panic!("expected a material");
+    };
+    let Some(Material::TextureMap(map)) = material
+      .iter()
+      .find(|property| matches!(property, Material::TextureMap(_)))
+    else {
+      panic!("expected a texture map");
+    };
+    assert!(map
+      .iter()
+      .any(|parameter| matches!(parameter, MaterialTextureMap::Name(_))));
+    assert!(map
+      .iter()
+      .any(|parameter| matches!(parameter, MaterialTextureMap::Amount(_))));
+    assert!(map
+      .iter()
+      .any(|parameter| matches!(parameter, MaterialTextureMap::Tiling(_))));
+    assert!(map
+      .iter()
+      .any(|parameter| matches!(parameter, MaterialTextureMap::Blur(_))));
+
+    let transform = uv_transform(&[
+      MaterialTextureMap::UScale(2.0),
+      MaterialTextureMap::VScale(3.0),
+      MaterialTextureMap::UOffset(0.5),
+      MaterialTextureMap::Angle(90.0),
+    ]);
+    let expected = [[0.0, -3.0, 0.5], [2.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+    for (actual, expected) in transform.into_iter().zip(expected) {
+      let close = actual
+        .iter()
+        .zip(expected)
+        .all(|(actual, expected)| (actual - expected).abs() < 1e-6);
+      assert!(close, "{actual:?} != {expected:?}");
+    }
+  }
+
+  #[test_log::test]
+  fn trimesh() {
+    let editor = read_editor("test/tower.3ds");
+    let meshes = editor
+      .iter()
+      .filter_map(|item| match item {
+        Editor::Object {
+          name,
+          kind: ObjectKind::TriMesh(mesh),
+          ..
+        } => Some((name.as_str(), mesh)),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(meshes.len(), 10);
+
+    let (name, mesh) = meshes[0];
+    assert_eq!(name, "Tower");
+    assert_eq!(mesh.vertices.len(), 444);
+    assert_eq!(mesh.uvs.len(), 444);
+    assert!(mesh.local.is_some());
+    assert_eq!(mesh.local_vertices().unwrap().len(), 444);
+    assert_eq!(mesh.faces.len(), 354);
+    assert_eq!(mesh.face_flags.len(), 354);
+    assert_eq!(mesh.smoothing_groups.len(), 354);
+    let normals = mesh.normals();
+    assert_eq!(normals.len(), 354);
+    assert!(normals
+      .iter()
+      .flatten()
+      .all(|normal| (math::dot(*normal, *normal) - 1.0).abs() < 1e-4));
+    assert!(mesh
+      .faces
+      .iter()
+      .flatten()
+      .all(|&index| usize::from(index) < mesh.vertices.len()));
+  }
+
+  #[test_log::test]
+  fn mapping_count_mismatch() {
+    #[rustfmt::skip]
+    let vertices: &[u8] = &[
+      0x4d, 0x4d, 0x4a, 0x00, 0x00, 0x00,
+        0x3d, 0x3d, 0x44, 0x00, 0x00, 0x00,
+          0x00, 0x40, 0x3e, 0x00, 0x00, 0x00, b'm', 0x00,
+            0x00, 0x41, 0x36, 0x00, 0x00, 0x00,
+              0x10, 0x41, 0x20, 0x00, 0x00, 0x00, 0x02, 0x00,
+    ];
+    let uvs: &[u8] = &[0x40, 0x41, 0x10, 0x00, 0x00, 0x00, 0x01, 0x00];
+    let data = [vertices, &[0; 24], uvs, &[0; 8]].concat();
     let mut data = Cursor::new(data.as_slice());
     let mut parser = Parser3DS::new(&mut data);
-    debug!("{:#?}", parser.read_main());
+    let error = parser.read_main().unwrap_err();
+    assert!(matches!(
+      error.kind(),
+      ErrorKind::MappingCountMismatch { vertices: 2, uvs: 1 }
+    ));
+    assert_eq!(error.offset(), 0x4a);
+    assert_eq!(error.chunk_id(), Some(TRI_MAPPINGCOORS));
+  }
+
+  #[test_log::test]
+  fn material_groups() {
+    let editor = read_editor("test/hornet.3ds");
+    let Some(Editor::Object {
+      kind: ObjectKind::TriMesh(mesh),
+      ..
+    }) = editor
+      .iter()
+      .find(|item| matches!(item, Editor::Object { name, .. } if name == "hull"))
+    else {
+      panic!("expected a hull mesh");
+    };
+
+    let groups = mesh.resolve_materials(&editor).collect::<Vec<_>>();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].0.material_name, "24 - Default");
+    assert_eq!(groups[0].0.faces.len(), 76);
+    assert!(groups[0].1.is_some());
+    assert_eq!(groups[1].0.material_name, "tracks");
+    assert_eq!(groups[1].0.faces.len(), 40);
+    assert!(groups[1].1.is_some());
+  }
+
+  #[test_log::test]
+  fn percentage_encodings() {
+    let material = chunk(
+      EDIT_MATERIAL,
+      &[
+        chunk(MATERIAL_NAME, b"mixed\0"),
+        chunk(MATERIAL_SHININESS, &chunk(PCT_INT, &50i16.to_le_bytes())),
+        chunk(MATERIAL_TRANSPARENCY, &chunk(PCT_FLOAT, &floats(&[50.0]))),
+        chunk(MATERIAL_SELF_ILLUMINATION, &chunk(PCT_INT, &(-20i16).to_le_bytes())),
+        chunk(
+          MATERIAL_TEXTURE_MAP,
+          &[
+            chunk(MATERIAL_TEXTURE_MAP_NAME, b"a.png\0"),
+            chunk(PCT_FLOAT, &floats(&[75.0])),
+          ]
+          .concat(),
+        ),
+      ]
+      .concat(),
+    );
+    let editor = parse_editor(&editor_file(&[material]));
+    let [Editor::Material(material)] = editor.as_slice() else {
+      panic!("unexpected editor items {editor:?}");
+    };
+    assert_eq!(material[1], Material::Shininess(0.5));
+    assert_eq!(material[2], Material::Transparency(0.5));
+    assert_eq!(material[3], Material::SelfIllumination(-0.2));
+    let Material::TextureMap(map) = &material[4] else {
+      panic!("expected a texture map");
+    };
+    assert_eq!(map[1], MaterialTextureMap::Amount(0.75));
+  }
+
+  #[test_log::test]
+  fn face_index_out_of_range() {
+    let vertices = chunk(TRI_VERTEXL, &[&3u16.to_le_bytes()[..], &[0; 36]].concat());
+    let faces = chunk(TRI_FACEL1, &[1u16, 0, 1, 3, 0].map(u16::to_le_bytes).concat());
+    let read = |mesh: &[Vec<u8>]| {
+      let object = chunk(
+        EDIT_OBJECT,
+        &[b"m\0".to_vec(), chunk(OBJ_TRIMESH, &mesh.concat())].concat(),
+      );
+      let data = chunk(MAIN3DS, &chunk(MAIN_EDITOR, &object));
+      let mut data = Cursor::new(data.as_slice());
+      Parser3DS::new(&mut data).read_main().unwrap_err()
+    };
+
+    let error = read(&[vertices.clone(), faces.clone()]);
+    assert!(matches!(
+      error.kind(),
+      ErrorKind::FaceIndexOutOfRange { index: 3, vertices: 3 }
+    ));
+    assert_eq!(error.offset(), 0x56);
+    assert_eq!(error.chunk_id(), Some(TRI_FACEL1));
+
+    // Faces stored before the vertices are checked at the end of the mesh.
+    let error = read(&[faces, vertices]);
+    assert!(matches!(
+      error.kind(),
+      ErrorKind::FaceIndexOutOfRange { index: 3, vertices: 3 }
+    ));
+    assert_eq!(error.chunk_id(), Some(OBJ_TRIMESH));
+  }
+
+  fn editor_file(items: &[Vec<u8>]) -> Vec<u8> {
+    chunk(MAIN3DS, &chunk(MAIN_EDITOR, &items.concat()))
+  }
+
+  fn floats(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+  }
+
+  #[test_log::test]
+  fn lights() {
+    let spot = chunk(
+      LIT_SPOT,
+      &[
+        floats(&[0.0, 0.0, 0.0, 30.0, 45.0]),
+        chunk(LIT_SPOT_ROLL, &floats(&[10.0])),
+        chunk(LIT_SHADOWED, &[]),
+        chunk(
+          LIT_LOCAL_SHADOW,
+          &[floats(&[0.5, 3.0]), 512_u16.to_le_bytes().to_vec()].concat(),
+        ),
+        chunk(LIT_SPOT_RECTANGULAR, &[]),
+      ]
+      .concat(),
+    );
+    let light = chunk(
+      OBJ_LIGHT,
+      &[
+        floats(&[1.0, 2.0, 3.0]),
+        chunk(COL_RGB, &floats(&[1.0, 0.5, 0.0])),
+        chunk(LIT_MULTIPLIER, &floats(&[2.0])),
+        chunk(LIT_OUTER_RANGE, &floats(&[100.0])),
+        spot,
+      ]
+      .concat(),
+    );
+    let data = editor_file(&[chunk(EDIT_OBJECT, &[&b"spot\0"[..], &light].concat())]);
+    let editor = parse_editor(&data);
+    let [Editor::Object {
+      name,
+      kind: ObjectKind::Light(light),
+      ..
+    }] = editor.as_slice()
+    else {
+      panic!("expected a single light");
+    };
+    assert_eq!(name, "spot");
+    assert_eq!(light.position, [1.0, 2.0, 3.0]);
+    assert_eq!(light.color.linear, Some([1.0, 0.5, 0.0]));
+    assert!(!light.off);
+    assert_eq!(light.multiplier, 2.0);
+    assert_eq!(light.inner_range, None);
+    assert_eq!(light.outer_range, Some(100.0));
+    let spot = light.spot.as_ref().unwrap();
+    assert_eq!((spot.hotspot, spot.falloff, spot.roll), (30.0, 45.0, 10.0));
+    assert!(spot.shadowed && spot.rectangular && !spot.overshoot);
+    assert_eq!(spot.shadow.map(|shadow| shadow.map_size), Some(512));
+  }
+
+  #[test_log::test]
+  fn cameras() {
+    let camera = chunk(
+      OBJ_CAMERA,
+      &[
+        floats(&[0.0, -100.0, 50.0, 0.0, 0.0, 0.0, 5.0, 43.456]),
+        chunk(CAM_RANGES, &floats(&[1.0, 1000.0])),
+      ]
+      .concat(),
+    );
+    let data = editor_file(&[chunk(EDIT_OBJECT, &[&b"camera\0"[..], &camera].concat())]);
+    let editor = parse_editor(&data);
+    let [Editor::Object {
+      kind: ObjectKind::Camera(camera),
+      ..
+    }] = editor.as_slice()
+    else {
+      panic!("expected a single camera");
+    };
+    assert_eq!(camera.position, [0.0, -100.0, 50.0]);
+    assert_eq!(camera.target, [0.0, 0.0, 0.0]);
+    assert_eq!(camera.roll, 5.0);
+    assert!((camera.fov() - 45.0).abs() < 1e-3);
+    assert_eq!((camera.near_range, camera.far_range), (Some(1.0), Some(1000.0)));
+    assert!(!camera.cone_visible);
+  }
+
+  #[test_log::test]
+  fn keyframer_hierarchy() {
+    let data = fs::read("test/hornet.3ds").unwrap();
+    let mut parser = Parser3DS::from_bytes(data.as_slice());
+    let main = parser.read_main().unwrap();
+    let Some(Main::Keyframer(keyframer)) = main.iter().find(|item| matches!(item, Main::Keyframer(_))) else {
+      panic!("expected a keyframer chunk");
+    };
+    assert_eq!(keyframer.revision, 5);
+    assert_eq!(keyframer.filename, "MAXSCENE");
+    assert_eq!(keyframer.length, 30);
+    assert_eq!(keyframer.segment, Some(0..=30));
+    assert_eq!(keyframer.current_frame, 0);
+    assert_eq!(keyframer.nodes.len(), 2);
+    assert_eq!(keyframer.roots().collect::<Vec<_>>(), [0]);
+
+    let hull = &keyframer.nodes[keyframer.find("hull").unwrap()];
+    assert_eq!(hull.kind, NodeKind::Object);
+    assert_eq!(hull.parent_id, None);
+    assert_eq!(hull.children, [1]);
+
+    let mount = &keyframer.nodes[keyframer.find("mount03").unwrap()];
+    assert_eq!(mount.id, 1);
+    assert_eq!(mount.parent_id, Some(hull.id));
+    assert_eq!(mount.parent, Some(0));
+    assert_eq!(mount.pivot, [0.0; 3]);
+  }
+
+  #[test_log::test]
+  fn keyframer_tracks() {
+    let track_header = |flags: u16, count: u32| [&flags.to_le_bytes()[..], &[0; 8], &count.to_le_bytes()].concat();
+    let position = [
+      track_header(0x0003, 2),
+      0u32.to_le_bytes().to_vec(),
+      0u16.to_le_bytes().to_vec(),
+      floats(&[1.0, 2.0, 3.0]),
+      10u32.to_le_bytes().to_vec(),
+      0b1_0001u16.to_le_bytes().to_vec(),
+      floats(&[0.5, 0.25, 4.0, 5.0, 6.0]),
+    ]
+    .concat();
+    let rotation = [
+      track_header(0x0002, 1),
+      vec![0; 6],
+      floats(&[std::f32::consts::FRAC_PI_2, 0.0, 0.0, 1.0]),
+    ]
+    .concat();
+    let hide = [
+      track_header(0, 2),
+      5u32.to_le_bytes().to_vec(),
+      vec![0; 2],
+      8u32.to_le_bytes().to_vec(),
+      vec![0; 2],
+    ]
+    .concat();
+    let node = [
+      chunk(NODE_HDR, &[&b"box\0"[..], &[0; 4], &0xFFFFu16.to_le_bytes()].concat()),
+      chunk(NODE_POS_TRACK, &position),
+      chunk(NODE_ROT_TRACK, &rotation),
+      chunk(NODE_HIDE_TRACK, &hide),
+    ]
+    .concat();
+    let data = chunk(MAIN3DS, &chunk(MAIN_KEYFRAMES, &chunk(KEYF_OBJDES, &node)));
+    let main = Parser3DS::from_bytes(data.as_slice()).read_main().unwrap();
+    let [Main::Keyframer(keyframer)] = main.as_slice() else {
+      panic!("expected a keyframer chunk");
+    };
+    let node = &keyframer.nodes[0];
+
+    let position = node.position.as_ref().unwrap();
+    assert!(position.is_loop());
+    assert!(!position.is_repeat());
+    assert_eq!(position.keys.len(), 2);
+    assert_eq!(position.keys[0].value, [1.0, 2.0, 3.0]);
+    assert_eq!(position.keys[1].frame, 10);
+    assert_eq!(position.keys[1].tension, 0.5);
+    assert_eq!(position.keys[1].continuity, 0.0);
+    assert_eq!(position.keys[1].ease_from, 0.25);
+    assert_eq!(position.keys[1].value, [4.0, 5.0, 6.0]);
+
+    let rotation = node.rotation.as_ref().unwrap();
+    assert!(rotation.is_repeat());
+    assert_eq!(rotation.keys[0].value.angle, std::f32::consts::FRAC_PI_2);
+    assert_eq!(rotation.keys[0].value.axis, [0.0, 0.0, 1.0]);
+
+    let hide = node.hide.as_ref().unwrap();
+    assert_eq!(hide.keys.iter().map(|key| key.frame).collect::<Vec<_>>(), [5, 8]);
+    assert!(node.scale.is_none());
+  }
+
+  #[test_log::test]
+  fn tower_tracks() {
+    let data = fs::read("test/tower.3ds").unwrap();
+    let main = Parser3DS::from_bytes(data.as_slice()).read_main().unwrap();
+    let Some(Main::Keyframer(keyframer)) = main.iter().find(|item| matches!(item, Main::Keyframer(_))) else {
+      panic!("expected a keyframer chunk");
+    };
+    for node in &keyframer.nodes {
+      assert_eq!(node.position.as_ref().unwrap().keys.len(), 1);
+      assert_eq!(node.rotation.as_ref().unwrap().keys.len(), 1);
+      assert_eq!(node.scale.as_ref().unwrap().keys[0].value, [1.0; 3]);
+    }
+  }
+
+  #[test_log::test]
+  fn unknown_chunks() {
+    let editor = read_editor("test/hornet.3ds");
+    assert_eq!(editor[3], Editor::MasterScale(1.0));
+
+    let material = chunk(
+      EDIT_MATERIAL,
+      &[
+        chunk(MATERIAL_NAME, b"plugin\0"),
+        chunk(0xA0F0, &[1, 2]),
+        chunk(MATERIAL_TWO_SIDED, &[]),
+        chunk(MATERIAL_TEXTURE_MAP, &chunk(0xA3F0, &[3])),
+      ]
+      .concat(),
+    );
+    let faces = [&0u16.to_le_bytes()[..], &chunk(0x4170, &[4])].concat();
+    let mesh = chunk(
+      OBJ_TRIMESH,
+      &[chunk(TRI_VISIBLE, &[5]), chunk(TRI_FACEL1, &faces)].concat(),
+    );
+    let object = chunk(
+      EDIT_OBJECT,
+      &[b"box\0".to_vec(), chunk(OBJ_UNKNWN01, &[]), mesh].concat(),
+    );
+    let dummy = chunk(EDIT_OBJECT, &[b"dummy\0".to_vec(), chunk(OBJ_UNKNWN02, &[6])].concat());
+    let editor = parse_editor(&editor_file(&[material, object, dummy]));
+
+    let [Editor::Material(material), Editor::Object {
+      kind: ObjectKind::TriMesh(mesh),
+      unknown,
+      ..
+    }, Editor::Unknown(dummy)] = editor.as_slice()
+    else {
+      panic!("unexpected editor items {editor:?}");
+    };
+    assert!(matches!(&material[1], Material::Unknown(chunk) if chunk.id == 0xA0F0 && chunk.data == [1, 2]));
+    assert!(matches!(material[2], Material::TwoSided));
+    let Material::TextureMap(map) = &material[3] else {
+      panic!("expected a texture map");
+    };
+    assert!(matches!(&map[0], MaterialTextureMap::Unknown(chunk) if chunk.id == 0xA3F0));
+    assert_eq!(unknown.iter().map(|chunk| chunk.id).collect::<Vec<_>>(), [OBJ_UNKNWN01]);
+    assert_eq!(mesh.unknown[0].id, TRI_VISIBLE);
+    assert_eq!(mesh.unknown[0].data, [5]);
+    assert_eq!(mesh.face_list_unknown[0].id, 0x4170);
+    assert_eq!(dummy.id, EDIT_OBJECT);
+    assert_eq!(dummy.data, b"dummy\0");
+    assert_eq!(dummy.children[0].id, OBJ_UNKNWN02);
+  }
+
+  #[test_log::test]
+  fn generic_reader() {
+    let data = fs::read("test/hornet.3ds").unwrap();
+    let expected = Parser3DS::from_bytes(&data).read_main().unwrap();
+    assert_eq!(
+      Parser3DS::from_path("test/hornet.3ds").unwrap().read_main().unwrap(),
+      expected
+    );
+
+    // Files stored inside a larger stream, such as an archive, are read from the position of the reader.
+    let mut archive = b"archive header".to_vec();
+    archive.extend_from_slice(&data);
+    archive.extend_from_slice(b"next entry");
+    let mut reader = Cursor::new(archive);
+    reader.set_position(14);
+    let mut parser = Parser3DS::new(&mut reader);
+    assert_eq!(parser.read_main().unwrap(), expected);
+    assert_eq!(reader.position(), 14 + data.len() as u64);
+
+    let Err(error) = Parser3DS::from_path("test/missing.3ds") else {
+      panic!("missing file was opened");
+    };
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+  }
+
+  #[test_log::test]
+  fn environment() {
+    let items = [
+      chunk(EDIT_CONFIG1, &floats(&[2.54])),
+      chunk(EDIT_AMBIENT, &chunk(COL_RGB, &floats(&[0.25, 0.5, 1.0]))),
+      chunk(EDIT_BACKGR, &chunk(COL_TRU, &[0, 51, 255])),
+    ];
+    let editor = parse_editor(&editor_file(&items));
+    assert_eq!(editor[0], Editor::MasterScale(2.54));
+    let [Editor::Ambient(ambient), Editor::Background(background)] = &editor[1..] else {
+      panic!("unexpected editor items {editor:?}");
+    };
+    assert_eq!(ambient.linear, Some([0.25, 0.5, 1.0]));
+    assert_eq!(background.linear, Some([0.0, 0.2, 1.0]));
+
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_editor(&editor).unwrap();
+    assert_eq!(writer.into_inner().into_inner(), items.concat());
+
+    // Errors point into the file itself.
+    let truncated = editor_file(&[chunk(EDIT_BACKGR, &chunk(COL_RGB, &floats(&[1.0])))]);
+    let error = Parser3DS::from_bytes(&truncated).read_main().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedEof));
+    assert_eq!(error.offset(), 0x1c);
+    assert_eq!(error.chunk_id(), Some(COL_RGB));
+  }
+
+  #[test_log::test]
+  fn truncated_input() {
+    let data = fs::read("test/tower.3ds").unwrap();
+    let mut parser = Parser3DS::from_bytes(&data[..data.len() / 2]);
+    let error = parser.read_main().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::BadChunkLength(_)));
+    assert_eq!(error.chunk_id(), Some(MAIN3DS));
+    assert_eq!(error.offset(), 0);
+  }
+
+  #[test_log::test]
+  fn error_path() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+      0x4d, 0x4d, 0x1c, 0x00, 0x00, 0x00,
+        0x3d, 0x3d, 0x16, 0x00, 0x00, 0x00,
+          0xff, 0xaf, 0x10, 0x00, 0x00, 0x00,
+            0x00, 0xa0, 0x0a, 0x00, 0x00, 0x00, b'a', 0xff, b'b', 0x00,
+    ];
+    let mut parser = Parser3DS::from_bytes(data);
+    let error = parser.read_main().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidString(_)));
+    assert_eq!(error.offset(), 0x18);
+    assert_eq!(error.chunk_id(), Some(MATERIAL_NAME));
+    assert_eq!(
+      error.to_string(),
+      "invalid string: invalid utf-8 sequence of 1 bytes from index 1 at offset 0x18 \
+       in MAIN3DS > MAIN_EDITOR > EDIT_MATERIAL > MATERIAL_NAME"
+    );
   }
 }
@@ -0,0 +1,62 @@
+use crate::tree::UnknownChunk;
+use crate::Color;
+
+/// Light source stored in an [`OBJ_LIGHT`](crate::chunks::OBJ_LIGHT) chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+  pub position: [f32; 3],
+  pub color: Color,
+  pub off: bool,
+  pub multiplier: f32,
+  /// Whether the light fades out between [`Light::inner_range`] and [`Light::outer_range`].
+  pub attenuation: bool,
+  pub inner_range: Option<f32>,
+  pub outer_range: Option<f32>,
+  /// Spotlight parameters, [`None`] for omni lights.
+  pub spot: Option<Spotlight>,
+  /// Sub-chunks the parser does not understand, in file order.
+  pub unknown: Vec<UnknownChunk>,
+}
+
+impl Default for Light {
+  fn default() -> Self {
+    Self {
+      position: [0.0; 3],
+      color: Color::default(),
+      off: false,
+      multiplier: 1.0,
+      attenuation: false,
+      inner_range: None,
+      outer_range: None,
+      spot: None,
+      unknown: Vec::new(),
+    }
+  }
+}
+
+/// Spotlight parameters stored in a [`LIT_SPOT`](crate::chunks::LIT_SPOT) chunk. Angles are in degrees.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Spotlight {
+  pub target: [f32; 3],
+  pub hotspot: f32,
+  pub falloff: f32,
+  pub roll: f32,
+  pub shadowed: bool,
+  pub shadow: Option<Shadow>,
+  pub cone_visible: bool,
+  pub rectangular: bool,
+  /// Width to height ratio of a rectangular spotlight.
+  pub aspect: Option<f32>,
+  pub overshoot: bool,
+  /// Sub-chunks the parser does not understand, in file order.
+  pub unknown: Vec<UnknownChunk>,
+}
+
+/// Shadow map parameters stored in a [`LIT_LOCAL_SHADOW`](crate::chunks::LIT_LOCAL_SHADOW) chunk.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Shadow {
+  pub bias: f32,
+  pub filter: f32,
+  pub map_size: u16,
+}
@@ -0,0 +1,116 @@
+//! Small vector helpers shared by the geometry code.
+
+pub type Vec3 = [f32; 3];
+/// Affine transform of row vectors, stored as the images of the three axes followed by the translation.
+pub type Matrix4x3 = [Vec3; 4];
+
+pub fn add(a: Vec3, b: Vec3) -> Vec3 {
+  [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn sub(a: Vec3, b: Vec3) -> Vec3 {
+  [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
+  [
+    a[1].mul_add(b[2], -a[2] * b[1]),
+    a[2].mul_add(b[0], -a[0] * b[2]),
+    a[0].mul_add(b[1], -a[1] * b[0]),
+  ]
+}
+
+pub fn dot(a: Vec3, b: Vec3) -> f32 {
+  a[0].mul_add(b[0], a[1].mul_add(b[1], a[2] * b[2]))
+}
+
+/// Returns the vector scaled to unit length, or the zero vector if it has no length.
+pub fn normalize(a: Vec3) -> Vec3 {
+  let length = dot(a, a).sqrt();
+  if length > 0.0 {
+    [a[0] / length, a[1] / length, a[2] / length]
+  } else {
+    [0.0; 3]
+  }
+}
+
+pub fn scale(a: Vec3, factor: f32) -> Vec3 {
+  [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+pub fn transform_point(matrix: &Matrix4x3, point: Vec3) -> Vec3 {
+  let [x, y, z] = point;
+  add(
+    add(add(scale(matrix[0], x), scale(matrix[1], y)), scale(matrix[2], z)),
+    matrix[3],
+  )
+}
+
+/// Returns the inverse of an affine transform, or [`None`] if it is singular.
+pub fn invert_affine(matrix: &Matrix4x3) -> Option<Matrix4x3> {
+  let [r0, r1, r2, translation] = *matrix;
+  let c0 = cross(r1, r2);
+  let c1 = cross(r2, r0);
+  let c2 = cross(r0, r1);
+  let determinant = dot(r0, c0);
+  // Relative to the volume the axes would span if they were orthogonal, so that tiny but valid scales still invert.
+  let volume = [r0, r1, r2]
+    .iter()
+    .map(|axis| dot(*axis, *axis).sqrt())
+    .product::<f32>();
+  if determinant.abs() <= f32::EPSILON * volume {
+    return None;
+  }
+
+  let axes = [0, 1, 2].map(|row| scale([c0[row], c1[row], c2[row]], 1.0 / determinant));
+  let mut inverse = [axes[0], axes[1], axes[2], [0.0; 3]];
+  inverse[3] = scale(transform_point(&inverse, translation), -1.0);
+  Some(inverse)
+}
+
+/// Composes two affine transforms into one that applies `first` and then `second`.
+pub fn multiply(first: &Matrix4x3, second: &Matrix4x3) -> Matrix4x3 {
+  let linear = |axis: Vec3| sub(transform_point(second, axis), second[3]);
+  [
+    linear(first[0]),
+    linear(first[1]),
+    linear(first[2]),
+    transform_point(second, first[3]),
+  ]
+}
+
+/// Quaternion stored as `[x, y, z, w]`.
+pub type Quat = [f32; 4];
+
+pub const QUAT_IDENTITY: Quat = [0.0, 0.0, 0.0, 1.0];
+
+/// Hamilton product, which rotates by `b` and then by `a`.
+pub fn quat_mul(a: Quat, b: Quat) -> Quat {
+  let (av, aw) = ([a[0], a[1], a[2]], a[3]);
+  let (bv, bw) = ([b[0], b[1], b[2]], b[3]);
+  let vector = add(add(scale(bv, aw), scale(av, bw)), cross(av, bv));
+  [vector[0], vector[1], vector[2], aw.mul_add(bw, -dot(av, bv))]
+}
+
+/// Returns the rotation of `angle` radians around `axis`, or the identity if the axis has no length.
+pub fn quat_from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+  if dot(axis, axis) <= 0.0 {
+    return QUAT_IDENTITY;
+  }
+
+  let axis = normalize(axis);
+  let (sin, cos) = (angle / 2.0).sin_cos();
+  [axis[0] * sin, axis[1] * sin, axis[2] * sin, cos]
+}
+
+/// Rotates a vector by a unit quaternion.
+pub fn quat_rotate(q: Quat, v: Vec3) -> Vec3 {
+  let (qv, qw) = ([q[0], q[1], q[2]], q[3]);
+  let t = scale(cross(qv, v), 2.0);
+  add(add(v, scale(t, qw)), cross(qv, t))
+}
+
+/// Returns the rotation as the images of the three axes.
+pub fn quat_to_axes(q: Quat) -> [Vec3; 3] {
+  [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].map(|axis| quat_rotate(q, axis))
+}
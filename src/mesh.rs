@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use crate::math::{add, cross, invert_affine, normalize, sub, transform_point, Vec3};
+use crate::tree::UnknownChunk;
+use crate::{find_material, Editor, Material};
+
+/// Triangle mesh stored in an [`OBJ_TRIMESH`](crate::chunks::OBJ_TRIMESH) chunk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TriMesh {
+  /// Vertex positions in world space, already transformed by [`TriMesh::local`].
+  pub vertices: Vec<[f32; 3]>,
+  /// Texture coordinates of each vertex, empty if the mesh is not mapped.
+  pub uvs: Vec<[f32; 2]>,
+  pub faces: Vec<[u16; 3]>,
+  /// Edge visibility and wrapping flags of each face, in the same order as [`TriMesh::faces`].
+  pub face_flags: Vec<u16>,
+  /// Smoothing group bit mask of each face, empty if the mesh has no smoothing information.
+  pub smoothing_groups: Vec<u32>,
+  /// Faces assigned to each material, in the order they are stored in the file.
+  pub material_groups: Vec<MaterialGroup>,
+  /// Local coordinate system of the object: the X, Y and Z axes followed by the origin, all in world space.
+  pub local: Option<[[f32; 3]; 4]>,
+  /// Sub-chunks of the mesh the parser does not understand, in file order.
+  pub unknown: Vec<UnknownChunk>,
+  /// Sub-chunks of the [`TRI_FACEL1`](crate::chunks::TRI_FACEL1) chunk the parser does not understand, in file order.
+  pub face_list_unknown: Vec<UnknownChunk>,
+}
+
+/// Faces of a mesh that use the same material, stored in a [`TRI_MATERIAL`](crate::chunks::TRI_MATERIAL) chunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaterialGroup {
+  pub material_name: String,
+  /// Indices into [`TriMesh::faces`].
+  pub faces: Vec<u16>,
+}
+
+impl MaterialGroup {
+  /// Finds the material used by this group among the parsed editor items.
+  #[must_use]
+  pub fn resolve<'a>(&self, items: &'a [Editor]) -> Option<&'a [Material]> {
+    find_material(items, &self.material_name)
+  }
+}
+
+impl TriMesh {
+  /// Pairs every material group with the material it refers to, if that material is defined in the file.
+  pub fn resolve_materials<'a>(
+    &'a self,
+    items: &'a [Editor],
+  ) -> impl Iterator<Item = (&'a MaterialGroup, Option<&'a [Material]>)> + 'a {
+    self.material_groups.iter().map(|group| (group, group.resolve(items)))
+  }
+
+  /// Returns the vertex positions in world space, as they are stored in the file.
+  #[must_use]
+  pub fn world_vertices(&self) -> &[[f32; 3]] {
+    &self.vertices
+  }
+
+  /// Returns the vertex positions in the local coordinate system of the object, relative to its pivot.
+  ///
+  /// Returns [`None`] if the local coordinate system is singular. Meshes without one are already in local space.
+  #[must_use]
+  pub fn local_vertices(&self) -> Option<Vec<[f32; 3]>> {
+    let Some(local) = &self.local else {
+      return Some(self.vertices.clone());
+    };
+    let inverse = invert_affine(local)?;
+    Some(
+      self
+        .vertices
+        .iter()
+        .map(|&vertex| transform_point(&inverse, vertex))
+        .collect(),
+    )
+  }
+
+  /// Generates a normal for every corner of every face, in the same order as [`TriMesh::faces`].
+  ///
+  /// Faces that share at least one smoothing group bit are smoothed together across vertices with the same position,
+  /// while all other edges stay hard. Faces without any smoothing group, or all faces if the mesh has no smoothing
+  /// information, are shaded flat.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a face refers to a vertex that does not exist, which the parser never produces.
+  #[must_use]
+  pub fn normals(&self) -> Vec<[Vec3; 3]> {
+    let face_normals = self
+      .faces
+      .iter()
+      .map(|face| {
+        let [a, b, c] = face.map(|index| self.vertices[usize::from(index)]);
+        cross(sub(b, a), sub(c, a))
+      })
+      .collect::<Vec<_>>();
+    let smoothing_group = |face: usize| self.smoothing_groups.get(face).copied().unwrap_or(0);
+
+    // Vertices are split at texture seams, so faces are connected by position rather than by vertex index.
+    let mut faces_at_position = HashMap::<[u32; 3], Vec<usize>>::new();
+    for (face, indices) in self.faces.iter().enumerate() {
+      for &index in indices {
+        let position = self.vertices[usize::from(index)].map(f32::to_bits);
+        faces_at_position.entry(position).or_default().push(face);
+      }
+    }
+
+    self
+      .faces
+      .iter()
+      .enumerate()
+      .map(|(face, indices)| {
+        let group = smoothing_group(face);
+        indices.map(|index| {
+          if group == 0 {
+            return normalize(face_normals[face]);
+          }
+
+          let position = self.vertices[usize::from(index)].map(f32::to_bits);
+          let mut faces = faces_at_position[&position].clone();
+          faces.dedup();
+          let normal = faces
+            .into_iter()
+            .filter(|&other| smoothing_group(other) & group != 0)
+            .fold([0.0; 3], |normal, other| add(normal, face_normals[other]));
+          normalize(normal)
+        })
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Two faces folded at a right angle along the edge between vertices 0 and 1.
+  fn hinge(smoothing_groups: Vec<u32>) -> TriMesh {
+    TriMesh {
+      vertices: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+      faces: vec![[0, 1, 2], [1, 0, 3]],
+      face_flags: vec![0, 0],
+      smoothing_groups,
+      ..TriMesh::default()
+    }
+  }
+
+  fn assert_close(actual: Vec3, expected: Vec3) {
+    let difference = sub(actual, expected);
+    assert!(
+      difference.iter().all(|value| value.abs() < 1e-6),
+      "{actual:?} != {expected:?}"
+    );
+  }
+
+  #[test]
+  fn local_vertices() {
+    // Rotated by 90 degrees around Z, scaled by 2 and moved to (10, 0, 0).
+    let mesh = TriMesh {
+      vertices: vec![[10.0, 0.0, 0.0], [10.0, 2.0, 0.0], [8.0, 0.0, 2.0]],
+      local: Some([[0.0, 2.0, 0.0], [-2.0, 0.0, 0.0], [0.0, 0.0, 2.0], [10.0, 0.0, 0.0]]),
+      ..TriMesh::default()
+    };
+    let local = mesh.local_vertices().unwrap();
+    assert_close(local[0], [0.0, 0.0, 0.0]);
+    assert_close(local[1], [1.0, 0.0, 0.0]);
+    assert_close(local[2], [0.0, 1.0, 1.0]);
+
+    // Uniform scales far below one give a tiny determinant but are still invertible.
+    let tiny = TriMesh {
+      vertices: vec![[0.004, 0.0, 0.0]],
+      local: Some([[0.004, 0.0, 0.0], [0.0, 0.004, 0.0], [0.0, 0.0, 0.004], [0.0; 3]]),
+      ..TriMesh::default()
+    };
+    assert_close(tiny.local_vertices().unwrap()[0], [1.0, 0.0, 0.0]);
+
+    let singular = TriMesh {
+      local: Some([[1.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0; 3]]),
+      ..mesh
+    };
+    assert_eq!(singular.local_vertices(), None);
+  }
+
+  #[test]
+  fn shared_smoothing_group() {
+    let normals = hinge(vec![0b01, 0b11]).normals();
+    let smooth = normalize([0.0, 1.0, 1.0]);
+    assert_close(normals[0][0], smooth);
+    assert_close(normals[0][1], smooth);
+    assert_close(normals[1][0], smooth);
+    assert_close(normals[1][1], smooth);
+    assert_close(normals[0][2], [0.0, 0.0, 1.0]);
+    assert_close(normals[1][2], [0.0, 1.0, 0.0]);
+  }
+
+  #[test]
+  fn separate_smoothing_groups() {
+    for smoothing_groups in [vec![0b01, 0b10], vec![0, 0], vec![]] {
+      let normals = hinge(smoothing_groups).normals();
+      for (first, second) in normals[0].into_iter().zip(normals[1]) {
+        assert_close(first, [0.0, 0.0, 1.0]);
+        assert_close(second, [0.0, 1.0, 0.0]);
+      }
+    }
+  }
+}
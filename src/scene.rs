@@ -0,0 +1,325 @@
+//! High-level view of a whole file, with the materials, objects and nodes collected into plain structs.
+
+use std::ops::Deref;
+use std::path::Path;
+
+use crate::camera::Camera;
+use crate::keyframer::Node;
+use crate::light::Light;
+use crate::mesh::TriMesh;
+use crate::{
+  Color, Editor, Main, Material as MaterialProperty, MaterialTextureMap, ObjectKind, Parser3DS, Result, Shading,
+};
+
+/// Contents of a file, built from the parsed [`Main`] items.
+///
+/// Chunks the parser does not understand are not part of the scene, they are only kept in the parsed items.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scene {
+  pub materials: Vec<Material>,
+  pub meshes: Vec<Named<TriMesh>>,
+  pub lights: Vec<Named<Light>>,
+  pub cameras: Vec<Named<Camera>>,
+  /// Keyframer nodes, linked through [`Node::parent`] and [`Node::children`] as in
+  /// [`Keyframer::nodes`](crate::keyframer::Keyframer::nodes).
+  pub nodes: Vec<Node>,
+  /// Ambient light color, stored in an [`EDIT_AMBIENT`](crate::chunks::EDIT_AMBIENT) chunk.
+  pub ambient: Option<Color>,
+  /// Solid background color, stored in an [`EDIT_BACKGR`](crate::chunks::EDIT_BACKGR) chunk.
+  pub background: Option<Color>,
+  /// Size of one unit in inches, stored in an [`EDIT_CONFIG1`](crate::chunks::EDIT_CONFIG1) chunk.
+  pub master_scale: Option<f32>,
+}
+
+/// Object of the scene together with its name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Named<T> {
+  pub name: String,
+  pub value: T,
+}
+
+impl<T> Deref for Named<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.value
+  }
+}
+
+/// Material with all of its properties, collected from an [`EDIT_MATERIAL`](crate::chunks::EDIT_MATERIAL) chunk.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Material {
+  pub name: String,
+  pub ambient: Color,
+  pub diffuse: Color,
+  pub specular: Color,
+  /// Fractions in the `0.0..=1.0` range, like the matching [`crate::Material`] variants.
+  pub shininess: Option<f32>,
+  pub shininess_strength: Option<f32>,
+  pub transparency: Option<f32>,
+  pub transparency_falloff: Option<f32>,
+  pub reflection_blur: Option<f32>,
+  pub self_illumination: Option<f32>,
+  pub two_sided: bool,
+  pub decal: bool,
+  pub additive_transparency: bool,
+  pub wireframe: bool,
+  pub wire_thickness: Option<f32>,
+  pub face_map: bool,
+  pub shading: Option<Shading>,
+  /// Texture maps and masks in the order they are stored in the file.
+  pub maps: Vec<TextureMap>,
+}
+
+/// Purpose of a texture map, given by the id of its chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapKind {
+  Texture,
+  Texture2,
+  Opacity,
+  Bump,
+  Specular,
+  Shininess,
+  SelfIllumination,
+  Reflection,
+  TextureMask,
+  TextureMask2,
+  OpacityMask,
+  BumpMask,
+  ShininessMask,
+  SpecularMask,
+  SelfIlluminationMask,
+  ReflectionMask,
+}
+
+/// Texture map or mask of a material.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureMap {
+  pub kind: MapKind,
+  /// Name of the image file, empty if the map has none.
+  pub file_name: String,
+  /// Parameters of the map other than the file name, in file order.
+  pub parameters: Vec<MaterialTextureMap>,
+}
+
+impl Material {
+  /// Collects the properties of a parsed material.
+  #[must_use]
+  pub fn from_properties(properties: &[MaterialProperty]) -> Self {
+    let mut material = Self::default();
+    for property in properties {
+      match property {
+        MaterialProperty::Name(name) => material.name.clone_from(name),
+        MaterialProperty::Ambient(color) => material.ambient = color.clone(),
+        MaterialProperty::Diffuse(color) => material.diffuse = color.clone(),
+        MaterialProperty::Specular(color) => material.specular = color.clone(),
+        MaterialProperty::Shininess(value) => material.shininess = Some(*value),
+        MaterialProperty::ShininessStrength(value) => material.shininess_strength = Some(*value),
+        MaterialProperty::Transparency(value) => material.transparency = Some(*value),
+        MaterialProperty::TransparencyFalloff(value) => material.transparency_falloff = Some(*value),
+        MaterialProperty::ReflectionBlur(value) => material.reflection_blur = Some(*value),
+        MaterialProperty::SelfIllumination(value) => material.self_illumination = Some(*value),
+        MaterialProperty::TwoSided => material.two_sided = true,
+        MaterialProperty::Decal => material.decal = true,
+        MaterialProperty::AdditiveTransparency => material.additive_transparency = true,
+        MaterialProperty::Wireframe => material.wireframe = true,
+        MaterialProperty::WireThickness(thickness) => material.wire_thickness = Some(*thickness),
+        MaterialProperty::FaceMap => material.face_map = true,
+        MaterialProperty::Shading(shading) => material.shading = Some(*shading),
+        MaterialProperty::Unknown(_) => {}
+        property => material.maps.extend(TextureMap::from_property(property)),
+      }
+    }
+
+    material
+  }
+
+  /// Finds the first map of the given kind.
+  #[must_use]
+  pub fn map(&self, kind: MapKind) -> Option<&TextureMap> {
+    self.maps.iter().find(|map| map.kind == kind)
+  }
+}
+
+impl TextureMap {
+  /// Collects the parameters of a texture map or mask property, returning `None` for other properties.
+  #[must_use]
+  pub fn from_property(property: &MaterialProperty) -> Option<Self> {
+    let (kind, map) = match property {
+      MaterialProperty::TextureMap(map) => (MapKind::Texture, map),
+      MaterialProperty::TextureMap2(map) => (MapKind::Texture2, map),
+      MaterialProperty::OpacityMap(map) => (MapKind::Opacity, map),
+      MaterialProperty::BumpMap(map) => (MapKind::Bump, map),
+      MaterialProperty::SpecularMap(map) => (MapKind::Specular, map),
+      MaterialProperty::ShininessMap(map) => (MapKind::Shininess, map),
+      MaterialProperty::SelfIlluminationMap(map) => (MapKind::SelfIllumination, map),
+      MaterialProperty::ReflectionMap(map) => (MapKind::Reflection, map),
+      MaterialProperty::TextureMask(map) => (MapKind::TextureMask, map),
+      MaterialProperty::TextureMask2(map) => (MapKind::TextureMask2, map),
+      MaterialProperty::OpacityMask(map) => (MapKind::OpacityMask, map),
+      MaterialProperty::BumpMask(map) => (MapKind::BumpMask, map),
+      MaterialProperty::ShininessMask(map) => (MapKind::ShininessMask, map),
+      MaterialProperty::SpecularMask(map) => (MapKind::SpecularMask, map),
+      MaterialProperty::SelfIlluminationMask(map) => (MapKind::SelfIlluminationMask, map),
+      MaterialProperty::ReflectionMask(map) => (MapKind::ReflectionMask, map),
+      _ => return None,
+    };
+
+    let mut file_name = String::new();
+    let mut parameters = Vec::new();
+    for parameter in map {
+      match parameter {
+        MaterialTextureMap::Name(name) => file_name.clone_from(name),
+        parameter => parameters.push(parameter.clone()),
+      }
+    }
+
+    Some(Self {
+      kind,
+      file_name,
+      parameters,
+    })
+  }
+}
+
+impl Scene {
+  /// Reads a file and builds its scene.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the file cannot be opened or is malformed.
+  pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+    let items = Parser3DS::from_path(path)?.read_main()?;
+    Ok(Self::from_main(items))
+  }
+
+  /// Builds the scene from the parsed items of a file.
+  #[must_use]
+  pub fn from_main(items: Vec<Main>) -> Self {
+    let mut scene = Self::default();
+    for item in items {
+      match item {
+        Main::Editor(editor) => scene.add_editor(editor),
+        Main::Keyframer(keyframer) => scene.nodes = keyframer.nodes,
+        Main::Version(_) | Main::Unknown(_) => {}
+      }
+    }
+
+    scene
+  }
+
+  fn add_editor(&mut self, items: Vec<Editor>) {
+    for item in items {
+      match item {
+        Editor::MasterScale(scale) => self.master_scale = Some(scale),
+        Editor::Ambient(color) => self.ambient = Some(color),
+        Editor::Background(color) => self.background = Some(color),
+        Editor::Material(properties) => self.materials.push(Material::from_properties(&properties)),
+        Editor::Object { name, kind, .. } => match kind {
+          ObjectKind::TriMesh(value) => self.meshes.push(Named { name, value }),
+          ObjectKind::Light(value) => self.lights.push(Named { name, value }),
+          ObjectKind::Camera(value) => self.cameras.push(Named { name, value }),
+        },
+        Editor::Version(_) | Editor::Unknown(_) => {}
+      }
+    }
+  }
+
+  /// Finds the material with the given name.
+  #[must_use]
+  pub fn material(&self, name: &str) -> Option<&Material> {
+    self.materials.iter().find(|material| material.name == name)
+  }
+
+  /// Finds the mesh with the given name.
+  #[must_use]
+  pub fn mesh(&self, name: &str) -> Option<&Named<TriMesh>> {
+    self.meshes.iter().find(|mesh| mesh.name == name)
+  }
+
+  /// Finds the light with the given name.
+  #[must_use]
+  pub fn light(&self, name: &str) -> Option<&Named<Light>> {
+    self.lights.iter().find(|light| light.name == name)
+  }
+
+  /// Finds the camera with the given name.
+  #[must_use]
+  pub fn camera(&self, name: &str) -> Option<&Named<Camera>> {
+    self.cameras.iter().find(|camera| camera.name == name)
+  }
+
+  /// Finds the first node animating the object with the given name.
+  #[must_use]
+  pub fn node(&self, name: &str) -> Option<&Node> {
+    self.nodes.iter().find(|node| node.name == name)
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tower() {
+    let scene = Scene::load("test/tower.3ds").unwrap();
+    assert_eq!(scene.master_scale, Some(1.0));
+    assert_eq!(scene.ambient, None);
+
+    let names = scene.materials.iter().map(|material| material.name.as_str());
+    assert_eq!(names.collect::<Vec<_>>(), ["Material #441", "Default"]);
+    let material = scene.material("Material #441").unwrap();
+    assert_eq!(material.shading, Some(Shading::Phong));
+    assert_eq!(material.shininess, Some(0.1));
+    let map = material.map(MapKind::Texture).unwrap();
+    assert_eq!(map.file_name, "TOWER_NE.JPG");
+    assert_eq!(map.parameters[0], MaterialTextureMap::Amount(1.0));
+    assert!(material.map(MapKind::Bump).is_none());
+
+    assert_eq!(scene.meshes.len(), 10);
+    let tower = scene.mesh("Tower").unwrap();
+    assert!(tower
+      .material_groups
+      .iter()
+      .any(|group| group.material_name == "Material #441"));
+    assert!(scene.mesh("Missing").is_none());
+    let node = scene.node("Tower").unwrap();
+    assert!(node.children.iter().all(|&child| scene.nodes[child].parent.is_some()));
+  }
+
+  #[test]
+  fn environment() {
+    let color = |rgb| Color {
+      linear: Some(rgb),
+      ..Color::default()
+    };
+    let editor = vec![
+      Editor::MasterScale(2.54),
+      Editor::Ambient(color([0.25, 0.5, 1.0])),
+      Editor::Background(color([0.0, 0.2, 1.0])),
+      Editor::Object {
+        name: "sun".to_owned(),
+        kind: ObjectKind::Light(Light::default()),
+        unknown: Vec::new(),
+      },
+      Editor::Object {
+        name: "view".to_owned(),
+        kind: ObjectKind::Camera(Camera {
+          lens: 50.0,
+          ..Camera::default()
+        }),
+        unknown: Vec::new(),
+      },
+    ];
+
+    let scene = Scene::from_main(vec![Main::Editor(editor)]);
+    assert_eq!(scene.master_scale, Some(2.54));
+    assert_eq!(scene.ambient.as_ref().unwrap().linear, Some([0.25, 0.5, 1.0]));
+    assert_eq!(scene.background.as_ref().unwrap().linear, Some([0.0, 0.2, 1.0]));
+    assert_eq!(scene.light("sun").unwrap().multiplier, Light::default().multiplier);
+    assert_eq!(scene.camera("view").unwrap().lens, 50.0);
+    assert!(scene.camera("sun").is_none());
+  }
+}
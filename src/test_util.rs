@@ -0,0 +1,7 @@
+//! Helpers shared by the unit tests of several modules.
+
+/// Builds a chunk with the given id around the given payload.
+pub fn chunk(id: u16, payload: &[u8]) -> Vec<u8> {
+  let length = u32::try_from(payload.len()).unwrap() + 6;
+  [&id.to_le_bytes()[..], &length.to_le_bytes(), payload].concat()
+}
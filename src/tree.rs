@@ -0,0 +1,323 @@
+//! Generic chunk tree that keeps the payloads of all chunks without interpreting them.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::ops::Deref;
+
+use crate::chunks::{
+  self, CHUNK_INFO_SIZE, COL_RGB, COL_RGB_GAMMA, COL_TRU, COL_TRU_GAMMA, EDIT_OBJECT, LIT_SPOT, OBJ_CAMERA, OBJ_LIGHT,
+  TRI_FACEL1,
+};
+use crate::{ChunkInfo, ErrorKind, Parser3DS, Result};
+
+/// Whole file read as a tree of [`RawChunk`]s.
+///
+/// Writing an unmodified tree with [`Writer3DS::write_chunk_tree`](crate::Writer3DS::write_chunk_tree) reproduces the
+/// original file byte for byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkTree {
+  pub root: RawChunk,
+  /// Bytes after the root chunk up to the end of the input, such as padding added by some exporters.
+  pub trailing: Vec<u8>,
+}
+
+/// Chunk with its payload kept as bytes.
+///
+/// Chunks compare equal when their ids, data, children and trailing bytes are equal, wherever they were read from.
+#[derive(Debug, Clone, Eq)]
+pub struct RawChunk {
+  pub id: u16,
+  /// Byte offset of the chunk header, relative to where the input was when the parser was created.
+  pub offset: u64,
+  /// Payload of the chunk. For containers this is only the data stored before the first sub-chunk.
+  pub data: Vec<u8>,
+  /// Sub-chunks of containers, see [`chunks::is_container`].
+  pub children: Vec<Self>,
+  /// Bytes at the end of a container that do not form a complete sub-chunk.
+  pub trailing: Vec<u8>,
+}
+
+impl PartialEq for RawChunk {
+  fn eq(&self, other: &Self) -> bool {
+    self.id == other.id && self.data == other.data && self.children == other.children && self.trailing == other.trailing
+  }
+}
+
+impl RawChunk {
+  /// Finds the first direct child with the given id.
+  #[must_use]
+  pub fn find(&self, id: u16) -> Option<&Self> {
+    self.children.iter().find(|child| child.id == id)
+  }
+
+  /// Iterates over the chunk and all of its descendants in file order.
+  pub fn descendants(&self) -> impl Iterator<Item = &Self> {
+    let mut stack = vec![self];
+    std::iter::from_fn(move || {
+      let chunk = stack.pop()?;
+      stack.extend(chunk.children.iter().rev());
+      Some(chunk)
+    })
+  }
+}
+
+/// Sub-chunk the parser does not understand, kept together with the sibling it was stored after.
+///
+/// The writer puts it back right after the same sibling, so that files keep the order of their chunks even where the
+/// writer adds or drops known chunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownChunk {
+  /// Sibling stored right before this chunk, [`None`] if this chunk came first.
+  pub after: Option<Sibling>,
+  pub chunk: RawChunk,
+}
+
+/// Sub-chunk identified by its id and by how many siblings with the same id were stored before it.
+///
+/// Colors count as the same sibling whether they are stored as bytes or as floats, because the writer picks the
+/// encoding on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sibling {
+  pub id: u16,
+  pub index: usize,
+}
+
+/// Sub-chunks of a chunk read or written so far.
+#[derive(Debug, Default)]
+pub(crate) struct Siblings {
+  counts: HashMap<u16, usize>,
+  last: Option<Sibling>,
+}
+
+impl Siblings {
+  /// Records the next sub-chunk.
+  pub(crate) fn push(&mut self, id: u16) {
+    let id = match id {
+      COL_TRU => COL_RGB,
+      COL_TRU_GAMMA => COL_RGB_GAMMA,
+      _ => id,
+    };
+    let count = self.counts.entry(id).or_default();
+    self.last = Some(Sibling { id, index: *count });
+    *count += 1;
+  }
+
+  /// Returns the sub-chunk recorded last.
+  pub(crate) const fn last(&self) -> Option<Sibling> {
+    self.last
+  }
+
+  /// Returns whether the given sub-chunk has been recorded.
+  pub(crate) fn contains(&self, sibling: Sibling) -> bool {
+    self.counts.get(&sibling.id).is_some_and(|&count| count > sibling.index)
+  }
+}
+
+impl Deref for UnknownChunk {
+  type Target = RawChunk;
+
+  fn deref(&self) -> &RawChunk {
+    &self.chunk
+  }
+}
+
+impl<R: Read + Seek> Parser3DS<R> {
+  /// Reads the whole file into a [`ChunkTree`], starting from the chunk at the current position.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the file is truncated or a chunk header is malformed.
+  pub fn read_chunk_tree(&mut self) -> Result<ChunkTree> {
+    let info = self.read_chunk_info()?;
+    let root = self.read_raw_chunk(&info)?;
+    self.seek_to_next_chunk(&info)?;
+    let length = self.remaining()?;
+    let trailing = self.read_bytes(length)?;
+
+    Ok(ChunkTree { root, trailing })
+  }
+
+  /// Reads the payload of a chunk whose header has just been read, together with its sub-chunks if it is a container.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the chunk is truncated or one of its sub-chunk headers is malformed.
+  pub fn read_raw_chunk(&mut self, info: &ChunkInfo) -> Result<RawChunk> {
+    let start = self.position;
+    let data_end = if chunks::is_container(info.id) {
+      start + self.read_container_data_length(info)?
+    } else {
+      info.get_end()
+    };
+    if data_end > info.get_end() {
+      return Err(self.error(ErrorKind::UnexpectedEof));
+    }
+    self.seek(start)?;
+    let data = self.read_bytes(data_end - start)?;
+
+    let mut children = Vec::new();
+    let mut trailing = Vec::new();
+    while self.position < info.get_end() {
+      if !self.starts_sub_chunk(info)? {
+        trailing = self.read_bytes(info.get_end() - self.position)?;
+        break;
+      }
+      let info = self.read_chunk_info()?;
+      children.push(self.read_raw_chunk(&info)?);
+      self.seek_to_next_chunk(&info)?;
+    }
+
+    Ok(RawChunk {
+      id: info.id,
+      offset: info.offset,
+      data,
+      children,
+      trailing,
+    })
+  }
+
+  /// Returns whether the bytes at the cursor hold the header of a sub-chunk that fits into the given chunk, leaving
+  /// the cursor where it was.
+  fn starts_sub_chunk(&mut self, info: &ChunkInfo) -> Result<bool> {
+    let start = self.position;
+    if info.get_end() - start < CHUNK_INFO_SIZE {
+      return Ok(false);
+    }
+    self.read_u16()?;
+    let length = u64::from(self.read_u32()?);
+    self.seek(start)?;
+
+    Ok(length >= CHUNK_INFO_SIZE && start + length <= info.get_end())
+  }
+
+  /// Returns the length of the data a container stores before its first sub-chunk, leaving the cursor anywhere.
+  fn read_container_data_length(&mut self, info: &ChunkInfo) -> Result<u64> {
+    let start = self.position;
+    Ok(match info.id {
+      EDIT_OBJECT => {
+        self.read_string(info)?;
+        self.position - start
+      }
+      TRI_FACEL1 => 2 + u64::from(self.read_u16()?) * 8,
+      OBJ_LIGHT => 12,
+      LIT_SPOT => 20,
+      OBJ_CAMERA => 32,
+      _ => 0,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+  use std::io::Cursor;
+
+  use super::*;
+  use crate::chunks::{
+    EDIT_BACKGR, EDIT_MATERIAL, KEYF_OBJDES, MAIN3DS, MAIN_EDITOR, MAIN_KEYFRAMES, MATERIAL_NAME, OBJ_TRIMESH,
+    TRI_MATERIAL,
+  };
+  use crate::test_util::chunk;
+  use crate::Writer3DS;
+
+  fn length(chunk: &RawChunk) -> u64 {
+    CHUNK_INFO_SIZE + chunk.data.len() as u64 + chunk.children.iter().map(length).sum::<u64>()
+  }
+
+  #[test]
+  fn hornet() {
+    let data = fs::read("test/hornet.3ds").unwrap();
+    let tree = Parser3DS::from_bytes(data.as_slice()).read_chunk_tree().unwrap();
+    assert_eq!(tree.root.id, MAIN3DS);
+    assert_eq!(length(&tree.root), data.len() as u64);
+
+    let editor = tree.root.find(MAIN_EDITOR).unwrap();
+    let material = editor.find(EDIT_MATERIAL).unwrap();
+    assert_eq!(material.find(MATERIAL_NAME).unwrap().data, b"24 - Default\0");
+
+    let object = editor.find(EDIT_OBJECT).unwrap();
+    assert_eq!(object.data, b"hull\0");
+    let faces = object.find(OBJ_TRIMESH).unwrap().find(TRI_FACEL1).unwrap();
+    let groups = faces.children.iter().filter(|child| child.id == TRI_MATERIAL);
+    assert_eq!(groups.count(), 2);
+
+    let keyframer = tree.root.find(MAIN_KEYFRAMES).unwrap();
+    let nodes = keyframer.descendants().filter(|chunk| chunk.id == KEYF_OBJDES);
+    assert_eq!(nodes.count(), 2);
+
+    // Every chunk accounts for exactly the bytes its header declares.
+    for chunk in tree.root.descendants() {
+      let offset = usize::try_from(chunk.offset).unwrap();
+      let declared = u32::from_le_bytes(data[offset + 2..offset + 6].try_into().unwrap());
+      assert_eq!(u64::from(declared), length(chunk));
+    }
+  }
+
+  #[test]
+  fn trailing_bytes() {
+    // Too short for a header, and a header declaring more bytes than the parent holds.
+    for garbage in [&[1, 2, 3][..], &[0x00, 0xa0, 0xff, 0, 0, 0, 9]] {
+      let material = chunk(EDIT_MATERIAL, &[&chunk(MATERIAL_NAME, b"a\0")[..], garbage].concat());
+      let data = chunk(MAIN3DS, &chunk(MAIN_EDITOR, &material));
+      let mut cursor = Cursor::new(data.as_slice());
+      let tree = Parser3DS::new(&mut cursor).read_chunk_tree().unwrap();
+
+      let material = tree.root.find(MAIN_EDITOR).unwrap().find(EDIT_MATERIAL).unwrap();
+      assert_eq!(material.children.len(), 1);
+      assert_eq!(material.trailing, garbage);
+
+      let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+      writer.write_chunk_tree(&tree).unwrap();
+      assert_eq!(writer.into_inner().into_inner(), data);
+    }
+  }
+
+  #[test]
+  fn bytes_after_root() {
+    let mut data = fs::read("test/hornet.3ds").unwrap();
+    data.extend([0, 0]);
+    let tree = Parser3DS::from_bytes(data.as_slice()).read_chunk_tree().unwrap();
+    assert_eq!(tree.trailing, [0, 0]);
+
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_chunk_tree(&tree).unwrap();
+    assert!(writer.into_inner().into_inner() == data);
+  }
+
+  #[test]
+  fn byte_exact() {
+    for path in ["test/hornet.3ds", "test/tower.3ds"] {
+      let data = fs::read(path).unwrap();
+      let tree = Parser3DS::from_bytes(data.as_slice()).read_chunk_tree().unwrap();
+
+      let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+      writer.write_chunk_tree(&tree).unwrap();
+      assert!(
+        writer.into_inner().into_inner() == data,
+        "{path} changed after a round trip"
+      );
+    }
+  }
+
+  #[test]
+  fn deeply_nested() {
+    // Enough levels to overflow the stack if the nesting were not limited.
+    let depth = 20_000;
+    let mut data = Vec::new();
+    for (level, id) in [MAIN3DS, MAIN_EDITOR]
+      .into_iter()
+      .chain(std::iter::repeat_n(EDIT_BACKGR, depth))
+      .enumerate()
+    {
+      let length = u32::try_from((depth + 2 - level) * 6).unwrap();
+      data.extend_from_slice(&id.to_le_bytes());
+      data.extend_from_slice(&length.to_le_bytes());
+    }
+
+    let error = Parser3DS::from_bytes(data.as_slice()).read_chunk_tree().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::NestingTooDeep));
+    let error = Parser3DS::from_bytes(data.as_slice()).read_main().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::NestingTooDeep));
+    assert_eq!(error.chunk_id(), Some(EDIT_BACKGR));
+  }
+}
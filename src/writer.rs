@@ -0,0 +1,885 @@
+//! Serializer that writes the parsed data back into a 3DS file.
+
+use std::io::{Seek, SeekFrom, Write};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::camera::Camera;
+use crate::chunks::{
+  CAM_RANGES, CAM_SEE_CONE, COL_RGB, COL_RGB_GAMMA, COL_TRU, COL_TRU_GAMMA, EDIT_AMBIENT, EDIT_BACKGR, EDIT_CONFIG1,
+  EDIT_MATERIAL, EDIT_OBJECT, EDIT_VERSION, KEYF_AMBIENT, KEYF_CAMERA, KEYF_CAMERA_TARGET, KEYF_CURTIME, KEYF_FRAMES,
+  KEYF_HDR, KEYF_LIGHT, KEYF_LIGHT_TARGET, KEYF_OBJDES, KEYF_SPOTLIGHT, LIT_ATTENUATE, LIT_INNER_RANGE,
+  LIT_LOCAL_SHADOW, LIT_MULTIPLIER, LIT_OFF, LIT_OUTER_RANGE, LIT_SEE_CONE, LIT_SHADOWED, LIT_SPOT, LIT_SPOT_ASPECT,
+  LIT_SPOT_OVERSHOOT, LIT_SPOT_RECTANGULAR, LIT_SPOT_ROLL, MAIN3DS, MAIN_EDITOR, MAIN_KEYFRAMES, MAIN_VERSION,
+  MATERIAL_ADDITIVE, MATERIAL_AMBIENT, MATERIAL_BUMP_MAP, MATERIAL_BUMP_MASK, MATERIAL_DECAL, MATERIAL_DIFFUSE,
+  MATERIAL_FACE_MAP, MATERIAL_NAME, MATERIAL_OPACITY_MAP, MATERIAL_OPACITY_MASK, MATERIAL_REFLECTION_BLUR,
+  MATERIAL_REFLECTION_MAP, MATERIAL_REFLECTION_MASK, MATERIAL_SELF_ILLUMINATION, MATERIAL_SELF_ILLUMINATION_MAP,
+  MATERIAL_SELF_ILLUMINATION_MASK, MATERIAL_SHADING, MATERIAL_SHININESS, MATERIAL_SHININESS_MAP,
+  MATERIAL_SHININESS_MASK, MATERIAL_SHININESS_STRENGTH, MATERIAL_SPECULAR, MATERIAL_SPECULAR_MAP,
+  MATERIAL_SPECULAR_MASK, MATERIAL_TEXTURE_MAP, MATERIAL_TEXTURE_MAP2, MATERIAL_TEXTURE_MAP_ANGLE,
+  MATERIAL_TEXTURE_MAP_BLUR, MATERIAL_TEXTURE_MAP_NAME, MATERIAL_TEXTURE_MAP_TILING, MATERIAL_TEXTURE_MAP_TINT1,
+  MATERIAL_TEXTURE_MAP_TINT2, MATERIAL_TEXTURE_MAP_TINT_B, MATERIAL_TEXTURE_MAP_TINT_G, MATERIAL_TEXTURE_MAP_TINT_R,
+  MATERIAL_TEXTURE_MAP_U_OFFSET, MATERIAL_TEXTURE_MAP_U_SCALE, MATERIAL_TEXTURE_MAP_V_OFFSET,
+  MATERIAL_TEXTURE_MAP_V_SCALE, MATERIAL_TEXTURE_MASK, MATERIAL_TEXTURE_MASK2, MATERIAL_TRANSPARENCY,
+  MATERIAL_TRANSPARENCY_FALLOFF, MATERIAL_TWO_SIDED, MATERIAL_WIRE, MATERIAL_WIRE_THICKNESS, NODE_BOUNDBOX,
+  NODE_COL_TRACK, NODE_FALL_TRACK, NODE_FOV_TRACK, NODE_HDR, NODE_HIDE_TRACK, NODE_HOT_TRACK, NODE_ID,
+  NODE_INSTANCE_NAME, NODE_MORPH_TRACK, NODE_PIVOT, NODE_POS_TRACK, NODE_ROLL_TRACK, NODE_ROT_TRACK, NODE_SCL_TRACK,
+  OBJ_CAMERA, OBJ_LIGHT, OBJ_TRIMESH, PCT_FLOAT, PCT_INT, TRI_FACEL1, TRI_LOCAL, TRI_MAPPINGCOORS, TRI_MATERIAL,
+  TRI_SMOOTH, TRI_VERTEXL,
+};
+use crate::keyframer::{Keyframer, Node, NodeKind, Track};
+use crate::light::{Light, Spotlight};
+use crate::mesh::TriMesh;
+use crate::tree::{ChunkTree, RawChunk, Siblings, UnknownChunk};
+use crate::{ChunkInfo, Color, Editor, Error, ErrorKind, Main, Material, MaterialTextureMap, ObjectKind, Result};
+
+/// Writes 3DS chunks, filling in the length of every chunk once it is complete.
+pub struct Writer3DS<W> {
+  data: W,
+  /// Chunks that have been started but not finished yet, from the root down.
+  path: Vec<ChunkInfo>,
+}
+
+impl<W: Write + Seek> Writer3DS<W> {
+  pub const fn new(data: W) -> Self {
+    Self { data, path: Vec::new() }
+  }
+
+  /// Returns the underlying writer.
+  pub fn into_inner(self) -> W {
+    self.data
+  }
+
+  fn error(&mut self, kind: impl Into<ErrorKind>) -> Error {
+    let offset = self.data.stream_position().unwrap_or_default();
+    Error::new(kind.into(), offset, self.path.clone())
+  }
+
+  /// Writes the header of a chunk with a placeholder length and enters it.
+  ///
+  /// Every chunk started this way must be finished with [`Writer3DS::end_chunk`].
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn begin_chunk(&mut self, id: u16) -> Result<()> {
+    let offset = self.data.stream_position().map_err(|error| self.error(error))?;
+    self.path.push(ChunkInfo {
+      id,
+      offset,
+      next_chunk_offset: 0,
+    });
+    self.write_u16(id)?;
+    self.write_u32(0)
+  }
+
+  /// Leaves the innermost chunk and writes its final length into its header.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the chunk is longer than a chunk header can describe or the underlying writer fails.
+  ///
+  /// # Panics
+  ///
+  /// Panics if no chunk has been started.
+  pub fn end_chunk(&mut self) -> Result<()> {
+    let end = self.data.stream_position().map_err(|error| self.error(error))?;
+    let offset = self.path.last().expect("no chunk to end").offset;
+    let length = u32::try_from(end - offset).map_err(|_| self.error(ErrorKind::ChunkTooLarge(end - offset)))?;
+
+    let result = self.data.seek(SeekFrom::Start(offset + 2));
+    result.map_err(|error| self.error(error))?;
+    self.write_u32(length)?;
+    let result = self.data.seek(SeekFrom::Start(end));
+    result.map_err(|error| self.error(error))?;
+    self.path.pop();
+
+    Ok(())
+  }
+
+  /// Writes a chunk whose payload is written by the given function.
+  fn write_chunk(&mut self, id: u16, write_payload: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+    self.begin_chunk(id)?;
+    write_payload(self)?;
+    self.end_chunk()
+  }
+
+  fn write_u8(&mut self, value: u8) -> Result<()> {
+    let result = self.data.write_u8(value);
+    result.map_err(|error| self.error(error))
+  }
+
+  fn write_u16(&mut self, value: u16) -> Result<()> {
+    let result = self.data.write_u16::<LittleEndian>(value);
+    result.map_err(|error| self.error(error))
+  }
+
+  fn write_u32(&mut self, value: u32) -> Result<()> {
+    let result = self.data.write_u32::<LittleEndian>(value);
+    result.map_err(|error| self.error(error))
+  }
+
+  fn write_f32(&mut self, value: f32) -> Result<()> {
+    let result = self.data.write_f32::<LittleEndian>(value);
+    result.map_err(|error| self.error(error))
+  }
+
+  fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+    let result = self.data.write_all(bytes);
+    result.map_err(|error| self.error(error))
+  }
+
+  fn write_vector(&mut self, vector: [f32; 3]) -> Result<()> {
+    vector.into_iter().try_for_each(|value| self.write_f32(value))
+  }
+
+  /// Writes the element count of an array.
+  fn write_count(&mut self, count: usize) -> Result<()> {
+    let count = u16::try_from(count).map_err(|_| self.error(ErrorKind::CountTooLarge(count)))?;
+    self.write_u16(count)
+  }
+
+  /// Writes a null-terminated string.
+  fn write_string(&mut self, value: &str) -> Result<()> {
+    self.write_bytes(value.as_bytes())?;
+    self.write_u8(0)
+  }
+
+  /// Writes the whole file as a [`MAIN3DS`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the data does not fit into the file format or the underlying writer fails.
+  pub fn write_main(&mut self, items: &[Main]) -> Result<()> {
+    self.write_chunk(MAIN3DS, |writer| {
+      for item in items {
+        match item {
+          Main::Version(version) => writer.write_chunk(MAIN_VERSION, |writer| writer.write_u32(*version))?,
+          Main::Editor(editor) => writer.write_chunk(MAIN_EDITOR, |writer| writer.write_editor(editor))?,
+          Main::Keyframer(keyframer) => {
+            writer.write_chunk(MAIN_KEYFRAMES, |writer| writer.write_keyframer(keyframer))?;
+          }
+          Main::Unknown(chunk) => writer.write_raw_chunk(chunk)?,
+        }
+      }
+
+      Ok(())
+    })
+  }
+
+  /// Writes the children of a [`MAIN_EDITOR`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the data does not fit into the file format or the underlying writer fails.
+  pub fn write_editor(&mut self, items: &[Editor]) -> Result<()> {
+    for item in items {
+      match item {
+        Editor::Version(version) => self.write_chunk(EDIT_VERSION, |writer| writer.write_u32(*version))?,
+        Editor::MasterScale(scale) => self.write_chunk(EDIT_CONFIG1, |writer| writer.write_f32(*scale))?,
+        Editor::Ambient(color) => self.write_chunk(EDIT_AMBIENT, |writer| writer.write_color(color))?,
+        Editor::Background(color) => self.write_chunk(EDIT_BACKGR, |writer| writer.write_color(color))?,
+        Editor::Material(material) => self.write_chunk(EDIT_MATERIAL, |writer| writer.write_material(material))?,
+        Editor::Object { name, kind, unknown } => self.write_chunk(EDIT_OBJECT, |writer| {
+          writer.write_string(name)?;
+          let mut unknown = Interleave::new(unknown);
+          let id = match kind {
+            ObjectKind::TriMesh(_) => OBJ_TRIMESH,
+            ObjectKind::Light(_) => OBJ_LIGHT,
+            ObjectKind::Camera(_) => OBJ_CAMERA,
+          };
+          unknown.before_known(writer, id)?;
+          match kind {
+            ObjectKind::TriMesh(mesh) => writer.write_chunk(OBJ_TRIMESH, |writer| writer.write_trimesh(mesh))?,
+            ObjectKind::Light(light) => writer.write_chunk(OBJ_LIGHT, |writer| writer.write_light(light))?,
+            ObjectKind::Camera(camera) => writer.write_chunk(OBJ_CAMERA, |writer| writer.write_camera(camera))?,
+          }
+          unknown.finish(writer)
+        })?,
+        Editor::Unknown(chunk) => self.write_raw_chunk(chunk)?,
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes the children of an [`OBJ_TRIMESH`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the mesh has more than 65535 vertices or faces or the underlying writer fails.
+  pub fn write_trimesh(&mut self, mesh: &TriMesh) -> Result<()> {
+    let mut unknown = Interleave::new(&mesh.unknown);
+    unknown.before_known(self, TRI_VERTEXL)?;
+    self.write_chunk(TRI_VERTEXL, |writer| {
+      writer.write_count(mesh.vertices.len())?;
+      mesh.vertices.iter().try_for_each(|vertex| writer.write_vector(*vertex))
+    })?;
+    if !mesh.uvs.is_empty() {
+      unknown.before_known(self, TRI_MAPPINGCOORS)?;
+      self.write_chunk(TRI_MAPPINGCOORS, |writer| {
+        writer.write_count(mesh.uvs.len())?;
+        mesh.uvs.iter().flatten().try_for_each(|value| writer.write_f32(*value))
+      })?;
+    }
+    if let Some(local) = mesh.local {
+      unknown.before_known(self, TRI_LOCAL)?;
+      self.write_chunk(TRI_LOCAL, |writer| {
+        local.into_iter().try_for_each(|row| writer.write_vector(row))
+      })?;
+    }
+    if !mesh.faces.is_empty() || !mesh.material_groups.is_empty() || !mesh.face_list_unknown.is_empty() {
+      unknown.before_known(self, TRI_FACEL1)?;
+      self.write_chunk(TRI_FACEL1, |writer| writer.write_face_list(mesh))?;
+    }
+    unknown.finish(self)
+  }
+
+  /// Writes the payload of a [`TRI_FACEL1`] chunk.
+  fn write_face_list(&mut self, mesh: &TriMesh) -> Result<()> {
+    self.write_count(mesh.faces.len())?;
+    for (index, face) in mesh.faces.iter().enumerate() {
+      face.iter().try_for_each(|&vertex| self.write_u16(vertex))?;
+      self.write_u16(mesh.face_flags.get(index).copied().unwrap_or_default())?;
+    }
+    let mut unknown = Interleave::new(&mesh.face_list_unknown);
+    for group in &mesh.material_groups {
+      unknown.before_known(self, TRI_MATERIAL)?;
+      self.write_chunk(TRI_MATERIAL, |writer| {
+        writer.write_string(&group.material_name)?;
+        writer.write_count(group.faces.len())?;
+        group.faces.iter().try_for_each(|&face| writer.write_u16(face))
+      })?;
+    }
+    if !mesh.smoothing_groups.is_empty() {
+      unknown.before_known(self, TRI_SMOOTH)?;
+      self.write_chunk(TRI_SMOOTH, |writer| {
+        mesh
+          .smoothing_groups
+          .iter()
+          .try_for_each(|&groups| writer.write_u32(groups))
+      })?;
+    }
+    unknown.finish(self)
+  }
+
+  /// Writes the payload of an [`OBJ_LIGHT`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_light(&mut self, light: &Light) -> Result<()> {
+    self.write_vector(light.position)?;
+    let mut unknown = Interleave::new(&light.unknown);
+    self.write_color_chunks(&light.color, &mut unknown)?;
+    if light.off {
+      unknown.before_known(self, LIT_OFF)?;
+      self.write_chunk(LIT_OFF, |_| Ok(()))?;
+    }
+    if light.attenuation {
+      unknown.before_known(self, LIT_ATTENUATE)?;
+      self.write_chunk(LIT_ATTENUATE, |_| Ok(()))?;
+    }
+    if let Some(range) = light.inner_range {
+      unknown.before_known(self, LIT_INNER_RANGE)?;
+      self.write_chunk(LIT_INNER_RANGE, |writer| writer.write_f32(range))?;
+    }
+    if let Some(range) = light.outer_range {
+      unknown.before_known(self, LIT_OUTER_RANGE)?;
+      self.write_chunk(LIT_OUTER_RANGE, |writer| writer.write_f32(range))?;
+    }
+    unknown.before_known(self, LIT_MULTIPLIER)?;
+    self.write_chunk(LIT_MULTIPLIER, |writer| writer.write_f32(light.multiplier))?;
+    if let Some(spot) = &light.spot {
+      unknown.before_known(self, LIT_SPOT)?;
+      self.write_chunk(LIT_SPOT, |writer| writer.write_spotlight(spot))?;
+    }
+    unknown.finish(self)
+  }
+
+  /// Writes the payload of a [`LIT_SPOT`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_spotlight(&mut self, spot: &Spotlight) -> Result<()> {
+    self.write_vector(spot.target)?;
+    self.write_f32(spot.hotspot)?;
+    self.write_f32(spot.falloff)?;
+    let mut unknown = Interleave::new(&spot.unknown);
+    unknown.before_known(self, LIT_SPOT_ROLL)?;
+    self.write_chunk(LIT_SPOT_ROLL, |writer| writer.write_f32(spot.roll))?;
+    if spot.shadowed {
+      unknown.before_known(self, LIT_SHADOWED)?;
+      self.write_chunk(LIT_SHADOWED, |_| Ok(()))?;
+    }
+    if let Some(shadow) = spot.shadow {
+      unknown.before_known(self, LIT_LOCAL_SHADOW)?;
+      self.write_chunk(LIT_LOCAL_SHADOW, |writer| {
+        writer.write_f32(shadow.bias)?;
+        writer.write_f32(shadow.filter)?;
+        writer.write_u16(shadow.map_size)
+      })?;
+    }
+    if spot.cone_visible {
+      unknown.before_known(self, LIT_SEE_CONE)?;
+      self.write_chunk(LIT_SEE_CONE, |_| Ok(()))?;
+    }
+    if spot.rectangular {
+      unknown.before_known(self, LIT_SPOT_RECTANGULAR)?;
+      self.write_chunk(LIT_SPOT_RECTANGULAR, |_| Ok(()))?;
+    }
+    if let Some(aspect) = spot.aspect {
+      unknown.before_known(self, LIT_SPOT_ASPECT)?;
+      self.write_chunk(LIT_SPOT_ASPECT, |writer| writer.write_f32(aspect))?;
+    }
+    if spot.overshoot {
+      unknown.before_known(self, LIT_SPOT_OVERSHOOT)?;
+      self.write_chunk(LIT_SPOT_OVERSHOOT, |_| Ok(()))?;
+    }
+    unknown.finish(self)
+  }
+
+  /// Writes the payload of an [`OBJ_CAMERA`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_camera(&mut self, camera: &Camera) -> Result<()> {
+    self.write_vector(camera.position)?;
+    self.write_vector(camera.target)?;
+    self.write_f32(camera.roll)?;
+    self.write_f32(camera.lens)?;
+    let mut unknown = Interleave::new(&camera.unknown);
+    if camera.cone_visible {
+      unknown.before_known(self, CAM_SEE_CONE)?;
+      self.write_chunk(CAM_SEE_CONE, |_| Ok(()))?;
+    }
+    if let (Some(near), Some(far)) = (camera.near_range, camera.far_range) {
+      unknown.before_known(self, CAM_RANGES)?;
+      self.write_chunk(CAM_RANGES, |writer| {
+        writer.write_f32(near)?;
+        writer.write_f32(far)
+      })?;
+    }
+    unknown.finish(self)
+  }
+
+  /// Writes the children of an [`EDIT_MATERIAL`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_material(&mut self, material: &[Material]) -> Result<()> {
+    for property in material {
+      match property {
+        Material::Name(name) => self.write_chunk(MATERIAL_NAME, |writer| writer.write_string(name))?,
+        Material::Ambient(color) => self.write_chunk(MATERIAL_AMBIENT, |writer| writer.write_color(color))?,
+        Material::Diffuse(color) => self.write_chunk(MATERIAL_DIFFUSE, |writer| writer.write_color(color))?,
+        Material::Specular(color) => {
+          self.write_chunk(MATERIAL_SPECULAR, |writer| writer.write_color(color))?;
+        }
+        Material::Shininess(value) => self.write_percentage(MATERIAL_SHININESS, *value)?,
+        Material::ShininessStrength(value) => self.write_percentage(MATERIAL_SHININESS_STRENGTH, *value)?,
+        Material::Transparency(value) => self.write_percentage(MATERIAL_TRANSPARENCY, *value)?,
+        Material::TransparencyFalloff(value) => self.write_percentage(MATERIAL_TRANSPARENCY_FALLOFF, *value)?,
+        Material::ReflectionBlur(value) => self.write_percentage(MATERIAL_REFLECTION_BLUR, *value)?,
+        Material::SelfIllumination(value) => self.write_percentage(MATERIAL_SELF_ILLUMINATION, *value)?,
+        Material::TwoSided => self.write_chunk(MATERIAL_TWO_SIDED, |_| Ok(()))?,
+        Material::Decal => self.write_chunk(MATERIAL_DECAL, |_| Ok(()))?,
+        Material::AdditiveTransparency => self.write_chunk(MATERIAL_ADDITIVE, |_| Ok(()))?,
+        Material::Wireframe => self.write_chunk(MATERIAL_WIRE, |_| Ok(()))?,
+        Material::WireThickness(thickness) => {
+          self.write_chunk(MATERIAL_WIRE_THICKNESS, |writer| writer.write_f32(*thickness))?;
+        }
+        Material::FaceMap => self.write_chunk(MATERIAL_FACE_MAP, |_| Ok(()))?,
+        Material::Shading(shading) => {
+          self.write_chunk(MATERIAL_SHADING, |writer| writer.write_u16((*shading).into()))?;
+        }
+        Material::TextureMap(map) => self.write_material_texture_map(MATERIAL_TEXTURE_MAP, map)?,
+        Material::TextureMap2(map) => self.write_material_texture_map(MATERIAL_TEXTURE_MAP2, map)?,
+        Material::OpacityMap(map) => self.write_material_texture_map(MATERIAL_OPACITY_MAP, map)?,
+        Material::BumpMap(map) => self.write_material_texture_map(MATERIAL_BUMP_MAP, map)?,
+        Material::SpecularMap(map) => self.write_material_texture_map(MATERIAL_SPECULAR_MAP, map)?,
+        Material::ShininessMap(map) => self.write_material_texture_map(MATERIAL_SHININESS_MAP, map)?,
+        Material::SelfIlluminationMap(map) => self.write_material_texture_map(MATERIAL_SELF_ILLUMINATION_MAP, map)?,
+        Material::ReflectionMap(map) => self.write_material_texture_map(MATERIAL_REFLECTION_MAP, map)?,
+        Material::TextureMask(map) => self.write_material_texture_map(MATERIAL_TEXTURE_MASK, map)?,
+        Material::TextureMask2(map) => self.write_material_texture_map(MATERIAL_TEXTURE_MASK2, map)?,
+        Material::OpacityMask(map) => self.write_material_texture_map(MATERIAL_OPACITY_MASK, map)?,
+        Material::BumpMask(map) => self.write_material_texture_map(MATERIAL_BUMP_MASK, map)?,
+        Material::ShininessMask(map) => self.write_material_texture_map(MATERIAL_SHININESS_MASK, map)?,
+        Material::SpecularMask(map) => self.write_material_texture_map(MATERIAL_SPECULAR_MASK, map)?,
+        Material::SelfIlluminationMask(map) => {
+          self.write_material_texture_map(MATERIAL_SELF_ILLUMINATION_MASK, map)?;
+        }
+        Material::ReflectionMask(map) => self.write_material_texture_map(MATERIAL_REFLECTION_MASK, map)?,
+        Material::Unknown(chunk) => self.write_raw_chunk(chunk)?,
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes a texture map chunk with the given id, such as [`MATERIAL_TEXTURE_MAP`].
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_material_texture_map(&mut self, id: u16, map: &[MaterialTextureMap]) -> Result<()> {
+    self.begin_chunk(id)?;
+    for parameter in map {
+      match parameter {
+        MaterialTextureMap::Name(name) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_NAME, |writer| writer.write_string(name))?;
+        }
+        MaterialTextureMap::Amount(amount) => self.write_percentage_value(*amount)?,
+        MaterialTextureMap::Tiling(tiling) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_TILING, |writer| writer.write_u16(tiling.0))?;
+        }
+        MaterialTextureMap::Blur(value) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_BLUR, |writer| writer.write_f32(*value))?;
+        }
+        MaterialTextureMap::UScale(value) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_U_SCALE, |writer| writer.write_f32(*value))?;
+        }
+        MaterialTextureMap::VScale(value) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_V_SCALE, |writer| writer.write_f32(*value))?;
+        }
+        MaterialTextureMap::UOffset(value) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_U_OFFSET, |writer| writer.write_f32(*value))?;
+        }
+        MaterialTextureMap::VOffset(value) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_V_OFFSET, |writer| writer.write_f32(*value))?;
+        }
+        MaterialTextureMap::Angle(value) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_ANGLE, |writer| writer.write_f32(*value))?;
+        }
+        MaterialTextureMap::Tint1(color) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_TINT1, |writer| writer.write_color_tru(*color))?;
+        }
+        MaterialTextureMap::Tint2(color) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_TINT2, |writer| writer.write_color_tru(*color))?;
+        }
+        MaterialTextureMap::RedTint(color) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_TINT_R, |writer| writer.write_color_tru(*color))?;
+        }
+        MaterialTextureMap::GreenTint(color) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_TINT_G, |writer| writer.write_color_tru(*color))?;
+        }
+        MaterialTextureMap::BlueTint(color) => {
+          self.write_chunk(MATERIAL_TEXTURE_MAP_TINT_B, |writer| writer.write_color_tru(*color))?;
+        }
+        MaterialTextureMap::Unknown(chunk) => self.write_raw_chunk(chunk)?,
+      }
+    }
+    self.end_chunk()
+  }
+
+  /// Writes the children of a chunk that stores a color.
+  fn write_color(&mut self, color: &Color) -> Result<()> {
+    let mut unknown = Interleave::new(&color.unknown);
+    self.write_color_chunks(color, &mut unknown)?;
+    unknown.finish(self)
+  }
+
+  /// Writes the color sub-chunks of a color, as bytes if that keeps the exact values and as floats otherwise.
+  fn write_color_chunks(&mut self, color: &Color, unknown: &mut Interleave) -> Result<()> {
+    for (rgb, tru_id, float_id) in [
+      (color.linear, COL_TRU, COL_RGB),
+      (color.gamma_corrected, COL_TRU_GAMMA, COL_RGB_GAMMA),
+    ] {
+      let Some(rgb) = rgb else {
+        continue;
+      };
+      unknown.before_known(self, float_id)?;
+      if rgb
+        .iter()
+        .all(|&value| (f32::from(to_byte(value)) / 255.0).to_bits() == value.to_bits())
+      {
+        self.write_chunk(tru_id, |writer| writer.write_color_tru(rgb))?;
+      } else {
+        self.write_chunk(float_id, |writer| writer.write_vector(rgb))?;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn write_color_tru(&mut self, rgb: [f32; 3]) -> Result<()> {
+    self.write_bytes(&rgb.map(to_byte))
+  }
+
+  /// Writes a chunk storing a percentage given as a fraction in the `0.0..=1.0` range.
+  fn write_percentage(&mut self, id: u16, value: f32) -> Result<()> {
+    self.write_chunk(id, |writer| writer.write_percentage_value(value))
+  }
+
+  /// Writes a percentage as a [`PCT_INT`] chunk if that keeps the exact value and as a [`PCT_FLOAT`] chunk otherwise.
+  #[allow(clippy::cast_possible_truncation)]
+  fn write_percentage_value(&mut self, value: f32) -> Result<()> {
+    let percent = (value * 100.0).round() as i16;
+    if (f32::from(percent) / 100.0).to_bits() == value.to_bits() {
+      self.write_chunk(PCT_INT, |writer| writer.write_u16(percent.cast_unsigned()))
+    } else {
+      self.write_chunk(PCT_FLOAT, |writer| writer.write_f32(value * 100.0))
+    }
+  }
+
+  /// Writes the children of a [`MAIN_KEYFRAMES`] chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_keyframer(&mut self, keyframer: &Keyframer) -> Result<()> {
+    let mut unknown = Interleave::new(&keyframer.unknown);
+    unknown.before_known(self, KEYF_HDR)?;
+    self.write_chunk(KEYF_HDR, |writer| {
+      writer.write_u16(keyframer.revision)?;
+      writer.write_string(&keyframer.filename)?;
+      writer.write_u32(keyframer.length)
+    })?;
+    if let Some(segment) = &keyframer.segment {
+      unknown.before_known(self, KEYF_FRAMES)?;
+      self.write_chunk(KEYF_FRAMES, |writer| {
+        writer.write_u32(*segment.start())?;
+        writer.write_u32(*segment.end())
+      })?;
+    }
+    unknown.before_known(self, KEYF_CURTIME)?;
+    self.write_chunk(KEYF_CURTIME, |writer| writer.write_u32(keyframer.current_frame))?;
+    for node in &keyframer.nodes {
+      let id = match node.kind {
+        NodeKind::Ambient => KEYF_AMBIENT,
+        NodeKind::Object => KEYF_OBJDES,
+        NodeKind::Camera => KEYF_CAMERA,
+        NodeKind::CameraTarget => KEYF_CAMERA_TARGET,
+        NodeKind::Light => KEYF_LIGHT,
+        NodeKind::LightTarget => KEYF_LIGHT_TARGET,
+        NodeKind::Spotlight => KEYF_SPOTLIGHT,
+      };
+      unknown.before_known(self, id)?;
+      self.write_chunk(id, |writer| writer.write_node(node))?;
+    }
+    unknown.finish(self)
+  }
+
+  /// Writes the children of a keyframer node chunk.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_node(&mut self, node: &Node) -> Result<()> {
+    let mut unknown = Interleave::new(&node.unknown);
+    unknown.before_known(self, NODE_ID)?;
+    self.write_chunk(NODE_ID, |writer| writer.write_u16(node.id))?;
+    unknown.before_known(self, NODE_HDR)?;
+    self.write_chunk(NODE_HDR, |writer| {
+      writer.write_string(&node.name)?;
+      writer.write_u16(node.flags[0])?;
+      writer.write_u16(node.flags[1])?;
+      writer.write_u16(node.parent_id.unwrap_or(u16::MAX))
+    })?;
+    if let Some(name) = &node.instance_name {
+      unknown.before_known(self, NODE_INSTANCE_NAME)?;
+      self.write_chunk(NODE_INSTANCE_NAME, |writer| writer.write_string(name))?;
+    }
+    if node.kind == NodeKind::Object {
+      unknown.before_known(self, NODE_PIVOT)?;
+      self.write_chunk(NODE_PIVOT, |writer| writer.write_vector(node.pivot))?;
+    }
+    if let Some([min, max]) = node.bounding_box {
+      unknown.before_known(self, NODE_BOUNDBOX)?;
+      self.write_chunk(NODE_BOUNDBOX, |writer| {
+        writer.write_vector(min)?;
+        writer.write_vector(max)
+      })?;
+    }
+
+    let vector = |writer: &mut Self, value: &[f32; 3]| writer.write_vector(*value);
+    let float = |writer: &mut Self, value: &f32| writer.write_f32(*value);
+    if let Some(track) = &node.position {
+      unknown.before_known(self, NODE_POS_TRACK)?;
+      self.write_track(NODE_POS_TRACK, track, vector)?;
+    }
+    if let Some(track) = &node.rotation {
+      unknown.before_known(self, NODE_ROT_TRACK)?;
+      self.write_track(NODE_ROT_TRACK, track, |writer, rotation| {
+        writer.write_f32(rotation.angle)?;
+        writer.write_vector(rotation.axis)
+      })?;
+    }
+    if let Some(track) = &node.scale {
+      unknown.before_known(self, NODE_SCL_TRACK)?;
+      self.write_track(NODE_SCL_TRACK, track, vector)?;
+    }
+    if let Some(track) = &node.fov {
+      unknown.before_known(self, NODE_FOV_TRACK)?;
+      self.write_track(NODE_FOV_TRACK, track, float)?;
+    }
+    if let Some(track) = &node.roll {
+      unknown.before_known(self, NODE_ROLL_TRACK)?;
+      self.write_track(NODE_ROLL_TRACK, track, float)?;
+    }
+    if let Some(track) = &node.color {
+      unknown.before_known(self, NODE_COL_TRACK)?;
+      self.write_track(NODE_COL_TRACK, track, vector)?;
+    }
+    if let Some(track) = &node.morph {
+      unknown.before_known(self, NODE_MORPH_TRACK)?;
+      self.write_track(NODE_MORPH_TRACK, track, |writer, name| writer.write_string(name))?;
+    }
+    if let Some(track) = &node.hotspot {
+      unknown.before_known(self, NODE_HOT_TRACK)?;
+      self.write_track(NODE_HOT_TRACK, track, float)?;
+    }
+    if let Some(track) = &node.falloff {
+      unknown.before_known(self, NODE_FALL_TRACK)?;
+      self.write_track(NODE_FALL_TRACK, track, float)?;
+    }
+    if let Some(track) = &node.hide {
+      unknown.before_known(self, NODE_HIDE_TRACK)?;
+      self.write_track(NODE_HIDE_TRACK, track, |_, ()| Ok(()))?;
+    }
+    unknown.finish(self)
+  }
+
+  /// Writes an animation track chunk, using the given function to write the value of each key.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the track has more than `u32::MAX` keys or the underlying writer fails.
+  pub fn write_track<T>(
+    &mut self,
+    id: u16,
+    track: &Track<T>,
+    mut write_value: impl FnMut(&mut Self, &T) -> Result<()>,
+  ) -> Result<()> {
+    self.begin_chunk(id)?;
+    self.write_u16(track.flags)?;
+    track.reserved.iter().try_for_each(|&word| self.write_u32(word))?;
+    let count = u32::try_from(track.keys.len()).map_err(|_| self.error(ErrorKind::CountTooLarge(track.keys.len())))?;
+    self.write_u32(count)?;
+    for key in &track.keys {
+      self.write_u32(key.frame)?;
+      let spline = [key.tension, key.continuity, key.bias, key.ease_to, key.ease_from];
+      let flags = (0..spline.len())
+        .filter(|&bit| spline[bit] != 0.0)
+        .fold(0, |flags, bit| flags | 1 << bit);
+      self.write_u16(flags)?;
+      for parameter in spline.into_iter().filter(|&parameter| parameter != 0.0) {
+        self.write_f32(parameter)?;
+      }
+      write_value(self, &key.value)?;
+    }
+    self.end_chunk()
+  }
+
+  /// Writes a chunk tree back exactly as it was read.
+  ///
+  /// Unlike [`Writer3DS::write_main`], nothing is re-encoded: chunk order, unknown chunks, float bit patterns and the
+  /// bytes around strings all come from the tree, so writing an unmodified tree reproduces the original file byte for
+  /// byte.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_chunk_tree(&mut self, tree: &ChunkTree) -> Result<()> {
+    self.write_raw_chunk(&tree.root)?;
+    self.write_bytes(&tree.trailing)
+  }
+
+  /// Writes a raw chunk and its children as they are, recomputing the chunk lengths.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the underlying writer fails.
+  pub fn write_raw_chunk(&mut self, chunk: &RawChunk) -> Result<()> {
+    self.write_chunk(chunk.id, |writer| {
+      writer.write_bytes(&chunk.data)?;
+      chunk
+        .children
+        .iter()
+        .try_for_each(|child| writer.write_raw_chunk(child))?;
+      writer.write_bytes(&chunk.trailing)
+    })
+  }
+}
+
+/// Converts a color component in the `0.0..=1.0` range into a byte.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn to_byte(value: f32) -> u8 {
+  (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Puts the unknown sub-chunks of a chunk back between its known sub-chunks, where they were read from.
+struct Interleave<'a> {
+  unknown: &'a [UnknownChunk],
+  written: Siblings,
+}
+
+impl<'a> Interleave<'a> {
+  fn new(unknown: &'a [UnknownChunk]) -> Self {
+    Self {
+      unknown,
+      written: Siblings::default(),
+    }
+  }
+
+  /// Writes the unknown chunks whose preceding sibling has been written, before the known chunk with the given id,
+  /// which the caller writes right after.
+  fn before_known<W: Write + Seek>(&mut self, writer: &mut Writer3DS<W>, id: u16) -> Result<()> {
+    while let [chunk, rest @ ..] = self.unknown {
+      if chunk.after.is_some_and(|after| !self.written.contains(after)) {
+        break;
+      }
+      writer.write_raw_chunk(chunk)?;
+      self.written.push(chunk.id);
+      self.unknown = rest;
+    }
+    self.written.push(id);
+
+    Ok(())
+  }
+
+  /// Writes the unknown chunks that were stored after all known ones.
+  fn finish<W: Write + Seek>(self, writer: &mut Writer3DS<W>) -> Result<()> {
+    self.unknown.iter().try_for_each(|chunk| writer.write_raw_chunk(chunk))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+  use std::io::Cursor;
+
+  use super::*;
+  use crate::keyframer::Key;
+  use crate::test_util::chunk;
+  use crate::Parser3DS;
+
+  fn round_trip(path: &str) {
+    let data = fs::read(path).unwrap();
+    let main = Parser3DS::from_bytes(data.as_slice()).read_main().unwrap();
+
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_main(&main).unwrap();
+    let written = writer.into_inner().into_inner();
+
+    assert_eq!(Parser3DS::from_bytes(written.as_slice()).read_main().unwrap(), main);
+    assert_eq!(
+      u32::from_le_bytes(written[2..6].try_into().unwrap()) as usize,
+      written.len()
+    );
+  }
+
+  #[test]
+  fn tower() {
+    round_trip("test/tower.3ds");
+  }
+
+  #[test]
+  fn hornet() {
+    round_trip("test/hornet.3ds");
+  }
+
+  #[test]
+  fn track_header() {
+    let track = Track {
+      flags: 0x0003,
+      reserved: [1, 2],
+      keys: vec![Key {
+        frame: 5,
+        tension: 0.5,
+        value: 30.0,
+        ..Key::default()
+      }],
+    };
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer
+      .write_track(NODE_FOV_TRACK, &track, |writer, value| writer.write_f32(*value))
+      .unwrap();
+    let written = writer.into_inner().into_inner();
+
+    let mut parser = Parser3DS::from_bytes(written.as_slice());
+    let info = parser.read_chunk_info().unwrap();
+    let read = parser.read_track(&info, |parser, _| parser.read_f32()).unwrap();
+    assert!(read.is_loop());
+    assert_eq!(read, track);
+  }
+
+  #[test]
+  fn unknown_chunk_order() {
+    let vertices = [&3u16.to_le_bytes()[..], &[0; 3 * 12]].concat();
+    let faces = [1u16, 0, 1, 2, 0].map(u16::to_le_bytes).concat();
+    let face_list = [
+      faces,
+      chunk(0x4170, &[1]),
+      chunk(
+        TRI_MATERIAL,
+        &[&b"a\0"[..], &1u16.to_le_bytes(), &0u16.to_le_bytes()].concat(),
+      ),
+      chunk(0x4171, &[2]),
+    ]
+    .concat();
+    let mesh = [
+      chunk(TRI_VERTEXL, &vertices),
+      chunk(0x4165, &[3]),
+      chunk(TRI_FACEL1, &face_list),
+    ]
+    .concat();
+    let object = chunk(
+      EDIT_OBJECT,
+      &[&b"m\0"[..], &chunk(OBJ_TRIMESH, &mesh), &chunk(0x4010, &[])].concat(),
+    );
+    let file = chunk(MAIN3DS, &chunk(MAIN_EDITOR, &object));
+
+    let mut cursor = Cursor::new(file.as_slice());
+    let main = Parser3DS::new(&mut cursor).read_main().unwrap();
+    let [Main::Editor(editor)] = main.as_slice() else {
+      panic!("unexpected main items {main:?}");
+    };
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_editor(editor).unwrap();
+    assert_eq!(writer.into_inner().into_inner(), object);
+  }
+
+  #[test]
+  fn unknown_chunks_follow_their_sibling() {
+    // The node has no NODE_ID, which the writer adds in front of it.
+    let header = chunk(NODE_HDR, &[&b"a\0"[..], &[0; 4], &u16::MAX.to_le_bytes()].concat());
+    let unknown = chunk(0xB0F0, &[1]);
+    let pivot = chunk(NODE_PIVOT, &[0; 12]);
+    let node = chunk(KEYF_OBJDES, &[header.clone(), unknown.clone(), pivot.clone()].concat());
+    let file = chunk(MAIN3DS, &chunk(MAIN_KEYFRAMES, &node));
+    let main = Parser3DS::from_bytes(file.as_slice()).read_main().unwrap();
+    let [Main::Keyframer(keyframer)] = main.as_slice() else {
+      panic!("unexpected main items {main:?}");
+    };
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_node(&keyframer.nodes[0]).unwrap();
+    let id = chunk(NODE_ID, &0u16.to_le_bytes());
+    assert_eq!(writer.into_inner().into_inner(), [id, header, unknown, pivot].concat());
+
+    // Colors read as floats are written as bytes when that keeps their values.
+    let floats = [1f32, 0.0, 0.0].map(f32::to_le_bytes).concat();
+    let unknown = chunk(0x0099, &[2]);
+    let gamma = chunk(COL_RGB_GAMMA, &[0.5f32; 3].map(f32::to_le_bytes).concat());
+    let color = chunk(
+      MATERIAL_AMBIENT,
+      &[chunk(COL_RGB, &floats), unknown.clone(), gamma.clone()].concat(),
+    );
+    let file = chunk(MAIN3DS, &chunk(MAIN_EDITOR, &chunk(EDIT_MATERIAL, &color)));
+    let main = Parser3DS::from_bytes(file.as_slice()).read_main().unwrap();
+    let [Main::Editor(editor)] = main.as_slice() else {
+      panic!("unexpected main items {main:?}");
+    };
+    let mut writer = Writer3DS::new(Cursor::new(Vec::new()));
+    writer.write_editor(editor).unwrap();
+    let color = chunk(
+      MATERIAL_AMBIENT,
+      &[chunk(COL_TRU, &[255, 0, 0]), unknown, gamma].concat(),
+    );
+    assert_eq!(writer.into_inner().into_inner(), chunk(EDIT_MATERIAL, &color));
+  }
+}